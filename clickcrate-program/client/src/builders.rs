@@ -0,0 +1,501 @@
+//! Instruction builders working from already fetched state. Every account is
+//! resolved from the arguments, including the Core collection, which shares its
+//! address with the Product Listing id, and the per-asset order oracles.
+
+use crate::pda;
+use anchor_lang::{
+    prelude::{AccountMeta, Pubkey},
+    solana_program::instruction::Instruction,
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use clickcrate_program::{
+    accounts,
+    constants::MAX_STOCKING_BATCH,
+    instruction,
+    state::{ClickCrateState, Order, OrderStatus, ProductListingState, ProgramConfig},
+    ID,
+};
+
+/// Payment details for listings paid in an SPL token instead of SOL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenPayment {
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl TokenPayment {
+    /// Associated token account of `owner` for the payment mint.
+    pub fn token_account(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.mint, &self.token_program)
+    }
+}
+
+fn build(
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(remaining_accounts);
+    Instruction {
+        program_id: ID,
+        accounts: metas,
+        data: data.data(),
+    }
+}
+
+/// `[asset, oracle]` pairs for the stocking instructions.
+fn stocking_accounts(products: &[Pubkey]) -> Vec<AccountMeta> {
+    products
+        .iter()
+        .flat_map(|product| {
+            [
+                AccountMeta::new(*product, false),
+                AccountMeta::new(pda::oracle_address(product), false),
+            ]
+        })
+        .collect()
+}
+
+fn token_account(payment: Option<TokenPayment>, owner: &Pubkey) -> Option<Pubkey> {
+    payment.map(|payment| payment.token_account(owner))
+}
+
+/// Places `products` in a ClickCrate. The first batch goes through
+/// `place_products` and the rest through `resume_placement`, one instruction
+/// per batch.
+pub fn place_products(
+    product_listing_id: &Pubkey,
+    clickcrate_id: &Pubkey,
+    authority: &Pubkey,
+    clickcrate_authority: &Pubkey,
+    products: &[Pubkey],
+    price: u64,
+    payment: Option<TokenPayment>,
+) -> Vec<Instruction> {
+    let mut batches = products.chunks(MAX_STOCKING_BATCH);
+    let first_batch = batches.next().unwrap_or_default();
+    let mut instructions = vec![build(
+        accounts::PlaceProducts {
+            config: pda::config_address(),
+            clickcrate: pda::clickcrate_address(clickcrate_id),
+            product_listing: pda::product_listing_address(product_listing_id),
+            vault: pda::vault_address(product_listing_id),
+            listing_collection: *product_listing_id,
+            payment_mint: payment.map(|payment| payment.mint),
+            vault_token_account: token_account(payment, &pda::vault_address(product_listing_id)),
+            authority: *authority,
+            clickcrate_authority: *clickcrate_authority,
+            core_program: mpl_core::ID,
+            token_program: payment.map(|payment| payment.token_program),
+            associated_token_program: payment.map(|_| associated_token::ID),
+            system_program: system_program::ID,
+        },
+        instruction::PlaceProducts {
+            product_listing_id: *product_listing_id,
+            clickcrate_id: *clickcrate_id,
+            price,
+        },
+        stocking_accounts(first_batch),
+    )];
+    instructions.extend(batches.map(|batch| {
+        build(
+            accounts::ResumePlacement {
+                config: pda::config_address(),
+                product_listing: pda::product_listing_address(product_listing_id),
+                clickcrate: pda::clickcrate_address(clickcrate_id),
+                listing_collection: *product_listing_id,
+                authority: *authority,
+                core_program: mpl_core::ID,
+                system_program: system_program::ID,
+            },
+            instruction::ResumePlacement {
+                product_listing_id: *product_listing_id,
+            },
+            stocking_accounts(batch),
+        )
+    }));
+    instructions
+}
+
+/// Restocks a placed listing, one `add_stock` instruction per batch.
+pub fn add_stock(
+    product_listing_id: &Pubkey,
+    clickcrate_id: &Pubkey,
+    authority: &Pubkey,
+    products: &[Pubkey],
+) -> Vec<Instruction> {
+    products
+        .chunks(MAX_STOCKING_BATCH)
+        .map(|batch| {
+            build(
+                accounts::AddStock {
+                    config: pda::config_address(),
+                    product_listing: pda::product_listing_address(product_listing_id),
+                    clickcrate: pda::clickcrate_address(clickcrate_id),
+                    listing_collection: *product_listing_id,
+                    authority: *authority,
+                    core_program: mpl_core::ID,
+                    system_program: system_program::ID,
+                },
+                instruction::AddStock {
+                    product_listing_id: *product_listing_id,
+                },
+                stocking_accounts(batch),
+            )
+        })
+        .collect()
+}
+
+/// Ends a placement that can't be finished, keeping the assets placed so far.
+pub fn abort_stocking(product_listing_id: &Pubkey, owner: &Pubkey) -> Instruction {
+    build(
+        accounts::AbortStocking {
+            product_listing: pda::product_listing_address(product_listing_id),
+            owner: *owner,
+        },
+        instruction::AbortStocking {
+            product_listing_id: *product_listing_id,
+        },
+        vec![],
+    )
+}
+
+/// Unstocks a listing, one `remove_products` instruction per batch. `products`
+/// must cover every asset the seller still holds for the removal to finish.
+pub fn remove_products(
+    product_listing: &ProductListingState,
+    clickcrate: &ClickCrateState,
+    treasury: &Pubkey,
+    authority: &Pubkey,
+    products: &[Pubkey],
+    payment: Option<TokenPayment>,
+) -> Vec<Instruction> {
+    let vault = pda::vault_address(&product_listing.id);
+    products
+        .chunks(MAX_STOCKING_BATCH)
+        .map(|batch| {
+            build(
+                accounts::RemoveProducts {
+                    config: pda::config_address(),
+                    clickcrate: pda::clickcrate_address(&clickcrate.id),
+                    product_listing: pda::product_listing_address(&product_listing.id),
+                    vault,
+                    listing_collection: product_listing.id,
+                    payment_mint: payment.map(|payment| payment.mint),
+                    vault_token_account: token_account(payment, &vault),
+                    owner_token_account: token_account(payment, &product_listing.owner),
+                    treasury: *treasury,
+                    treasury_token_account: token_account(payment, treasury),
+                    owner: product_listing.owner,
+                    authority: *authority,
+                    core_program: mpl_core::ID,
+                    token_program: payment.map(|payment| payment.token_program),
+                    system_program: system_program::ID,
+                },
+                instruction::RemoveProducts {
+                    product_listing_id: product_listing.id,
+                    clickcrate_id: clickcrate.id,
+                },
+                stocking_accounts(batch),
+            )
+        })
+        .collect()
+}
+
+/// Buys `product_id` from a listing placed in `clickcrate_id`. The listing
+/// owner co-signs purchases, so both they and the buyer must sign.
+pub fn make_purchase(
+    product_listing: &ProductListingState,
+    clickcrate_id: &Pubkey,
+    product_id: &Pubkey,
+    buyer: &Pubkey,
+    quantity: u64,
+    payment: Option<TokenPayment>,
+) -> Instruction {
+    let vault = pda::vault_address(&product_listing.id);
+    build(
+        accounts::MakePurchase {
+            config: pda::config_address(),
+            clickcrate: pda::clickcrate_address(clickcrate_id),
+            product_listing: pda::product_listing_address(&product_listing.id),
+            oracle: pda::oracle_address(product_id),
+            vault,
+            order: pda::order_address(&product_listing.id, product_listing.order_count),
+            listing_collection: product_listing.id,
+            product_account: *product_id,
+            payment_mint: payment.map(|payment| payment.mint),
+            buyer_token_account: token_account(payment, buyer),
+            vault_token_account: token_account(payment, &vault),
+            owner: product_listing.owner,
+            buyer: *buyer,
+            core_program: mpl_core::ID,
+            token_program: payment.map(|payment| payment.token_program),
+            system_program: system_program::ID,
+        },
+        instruction::MakePurchase {
+            product_listing_id: product_listing.id,
+            clickcrate_id: *clickcrate_id,
+            product_id: *product_id,
+            quantity,
+        },
+        vec![],
+    )
+}
+
+pub fn update_order_status(
+    order: &Order,
+    authority: &Pubkey,
+    new_order_status: OrderStatus,
+) -> Instruction {
+    build(
+        accounts::UpdateOrderStatus {
+            config: pda::config_address(),
+            product_listing: pda::product_listing_address(&order.product_listing_id),
+            oracle: pda::oracle_address(&order.product_id),
+            order: pda::order_address(&order.product_listing_id, order.id),
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::UpdateOrderStatus {
+            product_id: order.product_id,
+            product_listing_id: order.product_listing_id,
+            new_order_status,
+        },
+        vec![],
+    )
+}
+
+pub fn confirm_delivery(order: &Order) -> Instruction {
+    build(
+        accounts::ConfirmDelivery {
+            oracle: pda::oracle_address(&order.product_id),
+            order: pda::order_address(&order.product_listing_id, order.id),
+            buyer: order.buyer,
+        },
+        instruction::ConfirmDelivery {
+            product_listing_id: order.product_listing_id,
+        },
+        vec![],
+    )
+}
+
+pub fn open_dispute(order: &Order) -> Instruction {
+    build(
+        accounts::OpenDispute {
+            oracle: pda::oracle_address(&order.product_id),
+            order: pda::order_address(&order.product_listing_id, order.id),
+            buyer: order.buyer,
+        },
+        instruction::OpenDispute {
+            product_listing_id: order.product_listing_id,
+        },
+        vec![],
+    )
+}
+
+/// Pays an order out and delivers the product. Anyone can be the `payer` once
+/// the order can be released.
+pub fn complete_order(
+    order: &Order,
+    product_listing: &ProductListingState,
+    clickcrate: &ClickCrateState,
+    config: &ProgramConfig,
+    payer: &Pubkey,
+    payment: Option<TokenPayment>,
+) -> Instruction {
+    let vault = pda::vault_address(&order.product_listing_id);
+    build(
+        accounts::CompleteOrder {
+            config: pda::config_address(),
+            product_listing: pda::product_listing_address(&order.product_listing_id),
+            clickcrate: pda::clickcrate_address(&order.clickcrate_id),
+            vault,
+            oracle: pda::oracle_address(&order.product_id),
+            order: pda::order_address(&order.product_listing_id, order.id),
+            seller: product_listing.owner,
+            treasury: config.treasury,
+            clickcrate_owner: clickcrate.owner,
+            buyer: order.buyer,
+            product: order.product_id,
+            listing_collection: order.product_listing_id,
+            payer: *payer,
+            payment_mint: payment.map(|payment| payment.mint),
+            vault_token_account: token_account(payment, &vault),
+            seller_token_account: token_account(payment, &product_listing.owner),
+            treasury_token_account: token_account(payment, &config.treasury),
+            clickcrate_owner_token_account: token_account(payment, &clickcrate.owner),
+            core_program: mpl_core::ID,
+            token_program: payment.map(|payment| payment.token_program),
+            system_program: system_program::ID,
+        },
+        instruction::CompleteOrder {
+            product_listing_id: order.product_listing_id,
+        },
+        vec![],
+    )
+}
+
+pub fn refund_order(
+    order: &Order,
+    authority: &Pubkey,
+    payment: Option<TokenPayment>,
+) -> Instruction {
+    let vault = pda::vault_address(&order.product_listing_id);
+    build(
+        accounts::RefundOrder {
+            product_listing: pda::product_listing_address(&order.product_listing_id),
+            vault,
+            order: pda::order_address(&order.product_listing_id, order.id),
+            buyer: order.buyer,
+            authority: *authority,
+            payment_mint: payment.map(|payment| payment.mint),
+            vault_token_account: token_account(payment, &vault),
+            buyer_token_account: token_account(payment, &order.buyer),
+            token_program: payment.map(|payment| payment.token_program),
+            system_program: system_program::ID,
+        },
+        instruction::RefundOrder {
+            product_listing_id: order.product_listing_id,
+        },
+        vec![],
+    )
+}
+
+/// Closes a ClickCrate with no listings placed and returns its rent to the owner.
+pub fn close_clickcrate(clickcrate: &ClickCrateState) -> Instruction {
+    build(
+        accounts::CloseClickCrate {
+            clickcrate: pda::clickcrate_address(&clickcrate.id),
+            owner: clickcrate.owner,
+        },
+        instruction::CloseClickcrate {},
+        vec![],
+    )
+}
+
+/// Closes an unplaced listing with every order settled. Close its oracles first.
+pub fn close_product_listing(product_listing: &ProductListingState) -> Instruction {
+    build(
+        accounts::CloseProductListing {
+            product_listing: pda::product_listing_address(&product_listing.id),
+            vault: pda::vault_address(&product_listing.id),
+            owner: product_listing.owner,
+        },
+        instruction::CloseProductListing {},
+        vec![],
+    )
+}
+
+/// Detaches the oracle of a sold or unstocked product and returns its rent to
+/// `owner`, who holds the collection's update authority. Works whether or not
+/// the listing is still registered.
+pub fn close_oracle(product_listing_id: &Pubkey, owner: &Pubkey, product: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseOracle {
+            oracle: pda::oracle_address(product),
+            product: *product,
+            listing_collection: *product_listing_id,
+            owner: *owner,
+            core_program: mpl_core::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CloseOracle {
+            product_listing_id: *product_listing_id,
+            product_id: *product,
+        },
+        vec![],
+    )
+}
+
+/// Settles a disputed order, signed by the arbiter named in `config`.
+pub fn resolve_dispute(
+    order: &Order,
+    product_listing: &ProductListingState,
+    clickcrate: &ClickCrateState,
+    config: &ProgramConfig,
+    buyer_refund: u64,
+    payment: Option<TokenPayment>,
+) -> Instruction {
+    let vault = pda::vault_address(&order.product_listing_id);
+    build(
+        accounts::ResolveDispute {
+            config: pda::config_address(),
+            product_listing: pda::product_listing_address(&order.product_listing_id),
+            clickcrate: pda::clickcrate_address(&order.clickcrate_id),
+            vault,
+            oracle: pda::oracle_address(&order.product_id),
+            order: pda::order_address(&order.product_listing_id, order.id),
+            seller: product_listing.owner,
+            treasury: config.treasury,
+            clickcrate_owner: clickcrate.owner,
+            buyer: order.buyer,
+            product: order.product_id,
+            listing_collection: order.product_listing_id,
+            arbiter: config.arbiter,
+            payment_mint: payment.map(|payment| payment.mint),
+            vault_token_account: token_account(payment, &vault),
+            seller_token_account: token_account(payment, &product_listing.owner),
+            treasury_token_account: token_account(payment, &config.treasury),
+            clickcrate_owner_token_account: token_account(payment, &clickcrate.owner),
+            buyer_token_account: token_account(payment, &order.buyer),
+            core_program: mpl_core::ID,
+            token_program: payment.map(|payment| payment.token_program),
+            system_program: system_program::ID,
+        },
+        instruction::ResolveDispute {
+            product_listing_id: order.product_listing_id,
+            buyer_refund,
+        },
+        vec![],
+    )
+}
+
+pub fn migrate_clickcrate(clickcrate_id: &Pubkey, payer: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateClickCrate {
+            clickcrate: pda::clickcrate_address(clickcrate_id),
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateClickcrate {},
+        vec![],
+    )
+}
+
+pub fn migrate_product_listing(product_listing_id: &Pubkey, payer: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateProductListing {
+            product_listing: pda::product_listing_address(product_listing_id),
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateProductListing {},
+        vec![],
+    )
+}
+
+pub fn migrate_oracle(product_id: &Pubkey, payer: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateOracle {
+            oracle: pda::oracle_address(product_id),
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateOracle {},
+        vec![],
+    )
+}
+
+pub fn migrate_vault(product_listing_id: &Pubkey, payer: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateVault {
+            vault: pda::vault_address(product_listing_id),
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateVault {},
+        vec![],
+    )
+}
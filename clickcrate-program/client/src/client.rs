@@ -0,0 +1,154 @@
+//! High-level builders that fetch whatever state an instruction depends on.
+
+use crate::builders::{self, TokenPayment};
+use crate::error::{ClientError, Result};
+use crate::fetch;
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use clickcrate_program::state::ProductListingState;
+use solana_client::rpc_client::RpcClient;
+
+pub struct ClickCrateClient {
+    pub rpc: RpcClient,
+}
+
+impl ClickCrateClient {
+    pub fn new(rpc: RpcClient) -> Self {
+        Self { rpc }
+    }
+
+    /// Token payment details of a listing, or `None` if it is paid in SOL.
+    pub fn payment_for(
+        &self,
+        product_listing: &ProductListingState,
+    ) -> Result<Option<TokenPayment>> {
+        product_listing
+            .payment_mint
+            .map(|mint| {
+                Ok(TokenPayment {
+                    mint,
+                    token_program: fetch::fetch_token_program(&self.rpc, &mint)?,
+                })
+            })
+            .transpose()
+    }
+
+    /// Purchase `product_id` from a listing placed in `clickcrate_id`.
+    pub fn purchase(
+        &self,
+        product_listing_id: &Pubkey,
+        clickcrate_id: &Pubkey,
+        product_id: &Pubkey,
+        buyer: &Pubkey,
+        quantity: u64,
+    ) -> Result<Instruction> {
+        let product_listing = fetch::fetch_product_listing(&self.rpc, product_listing_id)?;
+        let payment = self.payment_for(&product_listing)?;
+        Ok(builders::make_purchase(
+            &product_listing,
+            clickcrate_id,
+            product_id,
+            buyer,
+            quantity,
+            payment,
+        ))
+    }
+
+    pub fn place_products(
+        &self,
+        product_listing_id: &Pubkey,
+        clickcrate_id: &Pubkey,
+        authority: &Pubkey,
+        clickcrate_authority: &Pubkey,
+        products: &[Pubkey],
+        price: u64,
+    ) -> Result<Vec<Instruction>> {
+        let product_listing = fetch::fetch_product_listing(&self.rpc, product_listing_id)?;
+        let payment = self.payment_for(&product_listing)?;
+        Ok(builders::place_products(
+            product_listing_id,
+            clickcrate_id,
+            authority,
+            clickcrate_authority,
+            products,
+            price,
+            payment,
+        ))
+    }
+
+    pub fn remove_products(
+        &self,
+        product_listing_id: &Pubkey,
+        authority: &Pubkey,
+        products: &[Pubkey],
+    ) -> Result<Vec<Instruction>> {
+        let config = fetch::fetch_config(&self.rpc)?;
+        let product_listing = fetch::fetch_product_listing(&self.rpc, product_listing_id)?;
+        let clickcrate_id = product_listing
+            .clickcrate_pos
+            .ok_or(ClientError::ProductNotPlaced)?;
+        let clickcrate = fetch::fetch_clickcrate(&self.rpc, &clickcrate_id)?;
+        let payment = self.payment_for(&product_listing)?;
+        Ok(builders::remove_products(
+            &product_listing,
+            &clickcrate,
+            &config.treasury,
+            authority,
+            products,
+            payment,
+        ))
+    }
+
+    pub fn complete_order(
+        &self,
+        product_listing_id: &Pubkey,
+        order_id: u64,
+        payer: &Pubkey,
+    ) -> Result<Instruction> {
+        let config = fetch::fetch_config(&self.rpc)?;
+        let order = fetch::fetch_order(&self.rpc, product_listing_id, order_id)?;
+        let product_listing = fetch::fetch_product_listing(&self.rpc, product_listing_id)?;
+        let clickcrate = fetch::fetch_clickcrate(&self.rpc, &order.clickcrate_id)?;
+        let payment = self.payment_for(&product_listing)?;
+        Ok(builders::complete_order(
+            &order,
+            &product_listing,
+            &clickcrate,
+            &config,
+            payer,
+            payment,
+        ))
+    }
+
+    pub fn refund_order(
+        &self,
+        product_listing_id: &Pubkey,
+        order_id: u64,
+        authority: &Pubkey,
+    ) -> Result<Instruction> {
+        let order = fetch::fetch_order(&self.rpc, product_listing_id, order_id)?;
+        let product_listing = fetch::fetch_product_listing(&self.rpc, product_listing_id)?;
+        let payment = self.payment_for(&product_listing)?;
+        Ok(builders::refund_order(&order, authority, payment))
+    }
+
+    pub fn resolve_dispute(
+        &self,
+        product_listing_id: &Pubkey,
+        order_id: u64,
+        buyer_refund: u64,
+    ) -> Result<Instruction> {
+        let config = fetch::fetch_config(&self.rpc)?;
+        let order = fetch::fetch_order(&self.rpc, product_listing_id, order_id)?;
+        let product_listing = fetch::fetch_product_listing(&self.rpc, product_listing_id)?;
+        let clickcrate = fetch::fetch_clickcrate(&self.rpc, &order.clickcrate_id)?;
+        let payment = self.payment_for(&product_listing)?;
+        Ok(builders::resolve_dispute(
+            &order,
+            &product_listing,
+            &clickcrate,
+            &config,
+            buyer_refund,
+            payment,
+        ))
+    }
+}
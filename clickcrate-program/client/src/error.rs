@@ -0,0 +1,27 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("RPC request failed: {0}")]
+    Rpc(Box<solana_client::client_error::ClientError>),
+
+    #[error("Failed to deserialize account: {0}")]
+    Deserialize(Box<anchor_lang::error::Error>),
+
+    #[error("Product Listing is not placed in a ClickCrate")]
+    ProductNotPlaced,
+}
+
+impl From<solana_client::client_error::ClientError> for ClientError {
+    fn from(err: solana_client::client_error::ClientError) -> Self {
+        Self::Rpc(Box::new(err))
+    }
+}
+
+impl From<anchor_lang::error::Error> for ClientError {
+    fn from(err: anchor_lang::error::Error) -> Self {
+        Self::Deserialize(Box::new(err))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
@@ -0,0 +1,46 @@
+//! Fetching and deserializing program accounts over RPC.
+
+use crate::error::Result;
+use crate::pda;
+use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use clickcrate_program::state::{
+    ClickCrateState, Order, OrderOracle, ProductListingState, ProgramConfig,
+};
+use solana_client::rpc_client::RpcClient;
+
+/// Deserializes an Anchor account, checking its discriminator.
+pub fn deserialize<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    Ok(T::try_deserialize(&mut &data[..])?)
+}
+
+pub fn fetch<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    deserialize(&rpc.get_account_data(address)?)
+}
+
+pub fn fetch_config(rpc: &RpcClient) -> Result<ProgramConfig> {
+    fetch(rpc, &pda::config_address())
+}
+
+pub fn fetch_clickcrate(rpc: &RpcClient, clickcrate_id: &Pubkey) -> Result<ClickCrateState> {
+    fetch(rpc, &pda::clickcrate_address(clickcrate_id))
+}
+
+pub fn fetch_product_listing(
+    rpc: &RpcClient,
+    product_listing_id: &Pubkey,
+) -> Result<ProductListingState> {
+    fetch(rpc, &pda::product_listing_address(product_listing_id))
+}
+
+pub fn fetch_order(rpc: &RpcClient, product_listing_id: &Pubkey, order_id: u64) -> Result<Order> {
+    fetch(rpc, &pda::order_address(product_listing_id, order_id))
+}
+
+pub fn fetch_oracle(rpc: &RpcClient, product_id: &Pubkey) -> Result<OrderOracle> {
+    fetch(rpc, &pda::oracle_address(product_id))
+}
+
+/// Token program that owns `mint`, either SPL Token or Token-2022.
+pub fn fetch_token_program(rpc: &RpcClient, mint: &Pubkey) -> Result<Pubkey> {
+    Ok(rpc.get_account(mint)?.owner)
+}
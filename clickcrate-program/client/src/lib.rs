@@ -0,0 +1,14 @@
+//! Off-chain helpers for the ClickCrate program: PDA derivation, account
+//! fetching and instruction builders that resolve every account an
+//! instruction needs.
+
+pub mod builders;
+pub mod client;
+pub mod error;
+pub mod fetch;
+pub mod pda;
+
+pub use builders::TokenPayment;
+pub use clickcrate_program::{self, state, ID};
+pub use client::ClickCrateClient;
+pub use error::ClientError;
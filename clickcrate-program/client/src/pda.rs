@@ -0,0 +1,52 @@
+//! Addresses of the program's accounts. Product Listing ids are the address of
+//! the listing's Metaplex Core collection.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use clickcrate_program::ID;
+
+pub fn config_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &ID).0
+}
+
+pub fn clickcrate_address(clickcrate_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"clickcrate", clickcrate_id.as_ref()], &ID).0
+}
+
+pub fn product_listing_address(product_listing_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"listing", product_listing_id.as_ref()], &ID).0
+}
+
+pub fn vault_address(product_listing_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", product_listing_id.as_ref()], &ID).0
+}
+
+/// Order oracle attached to a Core asset while it is placed.
+pub fn oracle_address(product_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"oracle", product_id.as_ref()], &ID).0
+}
+
+pub fn order_address(product_listing_id: &Pubkey, order_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"order",
+            product_listing_id.as_ref(),
+            &order_id.to_le_bytes(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+/// Associated token account holding a token-paid listing's escrow.
+pub fn vault_token_address(
+    product_listing_id: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    get_associated_token_address_with_program_id(
+        &vault_address(product_listing_id),
+        mint,
+        token_program,
+    )
+}
@@ -0,0 +1,427 @@
+// Each test binary uses its own subset of these helpers
+#![allow(dead_code)]
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::{
+    associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    token::spl_token::{self, state::Mint},
+};
+use clickcrate_client::{builders::TokenPayment, pda};
+use clickcrate_program::{
+    accounts, instruction,
+    state::{Origin, PlacementType, ProductCategory},
+    ID,
+};
+use mpl_core::{
+    instructions::{CreateCollectionV2Builder, CreateV2Builder},
+    types::{Attribute, Attributes, Plugin, PluginAuthorityPair},
+    Asset,
+};
+use solana_program_test::{
+    find_file, read_file, BanksClientError, ProgramTest, ProgramTestContext,
+};
+use solana_sdk::{
+    account::Account,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    packet::PACKET_DATA_SIZE,
+    program_pack::Pack,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+pub const PRICE: u64 = LAMPORTS_PER_SOL;
+
+/// A bank with ClickCrate deployed as an upgradeable program and Metaplex Core
+/// loaded from `tests/fixtures`. The seller owns the program upgrade authority,
+/// so it is also the config admin, treasury and arbiter.
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+    pub seller: Keypair,
+}
+
+fn programdata_address() -> Pubkey {
+    Pubkey::find_program_address(&[ID.as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+/// Deploys through the upgradeable loader, which `ProgramTest::add_program`
+/// doesn't, so `initialize_config` can check the upgrade authority.
+fn add_upgradeable_program(program_test: &mut ProgramTest, upgrade_authority: Pubkey) {
+    let elf = read_file(
+        find_file("clickcrate_program.so")
+            .expect("clickcrate_program.so not found, run the tests with `cargo test-sbf`"),
+    );
+    let programdata_address = programdata_address();
+    let mut programdata = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(upgrade_authority),
+    })
+    .unwrap();
+    programdata.resize(UpgradeableLoaderState::size_of_programdata_metadata(), 0);
+    programdata.extend_from_slice(&elf);
+    let program = bincode::serialize(&UpgradeableLoaderState::Program {
+        programdata_address,
+    })
+    .unwrap();
+
+    for (address, data, executable) in [
+        (programdata_address, programdata, false),
+        (ID, program, true),
+    ] {
+        program_test.add_account(
+            address,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner: bpf_loader_upgradeable::id(),
+                executable,
+                rent_epoch: 0,
+            },
+        );
+    }
+}
+
+impl TestEnv {
+    pub async fn start() -> Self {
+        let seller = Keypair::new();
+        let mut program_test = ProgramTest::default();
+        program_test.prefer_bpf(true);
+        program_test.add_program("mpl_core", mpl_core::ID, None);
+        add_upgradeable_program(&mut program_test, seller.pubkey());
+        program_test.add_account(
+            seller.pubkey(),
+            Account {
+                lamports: 100 * LAMPORTS_PER_SOL,
+                owner: system_program::id(),
+                ..Account::default()
+            },
+        );
+
+        let mut env = Self {
+            context: program_test.start_with_context().await,
+            seller,
+        };
+        let seller = env.seller.insecure_clone();
+        env.process(
+            &[ix(
+                accounts::InitializeConfig {
+                    config: pda::config_address(),
+                    program: ID,
+                    program_data: programdata_address(),
+                    admin: seller.pubkey(),
+                    system_program: system_program::id(),
+                },
+                instruction::InitializeConfig {
+                    treasury: seller.pubkey(),
+                },
+            )],
+            &[&seller],
+        )
+        .await
+        .unwrap();
+        env
+    }
+
+    /// Sends `instructions` in one transaction paid for by the context payer.
+    /// Each transaction waits for a fresh blockhash, so retrying an instruction
+    /// after a failure isn't rejected as already processed.
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let payer = self.context.payer.insecure_clone();
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_instructions =
+            vec![ComputeBudgetInstruction::set_compute_unit_limit(1_400_000)];
+        all_instructions.extend_from_slice(instructions);
+        let mut all_signers = vec![&payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            &all_instructions,
+            Some(&payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        // The bank doesn't enforce the packet size limit that validators do
+        assert!(
+            bincode::serialize(&transaction).unwrap().len() <= PACKET_DATA_SIZE,
+            "transaction too large to send"
+        );
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    pub async fn account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("account {address} not found"));
+        T::try_deserialize(&mut &account.data[..]).unwrap()
+    }
+
+    pub async fn exists(&mut self, address: &Pubkey) -> bool {
+        self.context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    pub async fn balance(&mut self, address: &Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(*address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn asset_owner(&mut self, asset: &Pubkey) -> Pubkey {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*asset)
+            .await
+            .unwrap()
+            .unwrap();
+        Asset::deserialize(&account.data).unwrap().base.owner
+    }
+
+    pub async fn funded_keypair(&mut self, lamports: u64) -> Keypair {
+        let keypair = Keypair::new();
+        let payer = self.context.payer.pubkey();
+        self.process(
+            &[system_instruction::transfer(
+                &payer,
+                &keypair.pubkey(),
+                lamports,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        keypair
+    }
+
+    /// Creates an SPL token mint and an empty token account for the seller,
+    /// who also receives the treasury's share of token payments.
+    pub async fn create_payment_mint(&mut self) -> TokenPayment {
+        let seller = self.seller.insecure_clone();
+        let mint = Keypair::new();
+        let payment = TokenPayment {
+            mint: mint.pubkey(),
+            token_program: spl_token::id(),
+        };
+        self.process(
+            &[
+                system_instruction::create_account(
+                    &seller.pubkey(),
+                    &mint.pubkey(),
+                    Rent::default().minimum_balance(Mint::LEN),
+                    Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_mint2(
+                    &spl_token::id(),
+                    &mint.pubkey(),
+                    &seller.pubkey(),
+                    None,
+                    6,
+                )
+                .unwrap(),
+                create_associated_token_account_idempotent(
+                    &seller.pubkey(),
+                    &seller.pubkey(),
+                    &mint.pubkey(),
+                    &spl_token::id(),
+                ),
+            ],
+            &[&seller, &mint],
+        )
+        .await
+        .unwrap();
+        payment
+    }
+
+    /// Registers and activates a ClickCrate owned and managed by the seller.
+    pub async fn register_clickcrate(&mut self) -> Pubkey {
+        let seller = self.seller.insecure_clone();
+        self.register_managed_clickcrate(&seller, &seller.pubkey())
+            .await
+    }
+
+    /// Registers and activates a ClickCrate owned by `owner`.
+    pub async fn register_managed_clickcrate(
+        &mut self,
+        owner: &Keypair,
+        manager: &Pubkey,
+    ) -> Pubkey {
+        let clickcrate_id = Pubkey::new_unique();
+        let clickcrate = pda::clickcrate_address(&clickcrate_id);
+        self.process(
+            &[
+                ix(
+                    accounts::RegisterClickCrate {
+                        clickcrate,
+                        owner: owner.pubkey(),
+                        system_program: system_program::id(),
+                    },
+                    instruction::RegisterClickcrate {
+                        id: clickcrate_id,
+                        eligible_placement_type: PlacementType::Digitalreplica,
+                        eligible_product_categories: vec![ProductCategory::Clothing],
+                        manager: *manager,
+                        commission_basis_points: 0,
+                    },
+                ),
+                ix(
+                    accounts::ActivateClickCrate {
+                        clickcrate,
+                        authority: owner.pubkey(),
+                    },
+                    instruction::ActivateClickcrate {},
+                ),
+            ],
+            &[owner],
+        )
+        .await
+        .unwrap();
+        clickcrate_id
+    }
+
+    /// Creates a Core collection holding `assets` products owned by the seller,
+    /// returning the collection address, which is also the Product Listing id.
+    pub async fn create_listing_collection(&mut self, assets: usize) -> (Pubkey, Vec<Pubkey>) {
+        let seller = self.seller.insecure_clone();
+        let collection = Keypair::new();
+        self.process(
+            &[CreateCollectionV2Builder::new()
+                .collection(collection.pubkey())
+                .update_authority(Some(seller.pubkey()))
+                .payer(seller.pubkey())
+                .name("ClickCrate Test Listing".to_string())
+                .uri("https://example.com/listing.json".to_string())
+                .instruction()],
+            &[&seller, &collection],
+        )
+        .await
+        .unwrap();
+
+        let mut products = Vec::with_capacity(assets);
+        for _ in 0..assets {
+            let asset = Keypair::new();
+            self.process(
+                &[CreateV2Builder::new()
+                    .asset(asset.pubkey())
+                    .collection(Some(collection.pubkey()))
+                    .authority(Some(seller.pubkey()))
+                    .payer(seller.pubkey())
+                    .owner(Some(seller.pubkey()))
+                    .name("ClickCrate Test Product".to_string())
+                    .uri("https://example.com/product.json".to_string())
+                    .plugins(vec![PluginAuthorityPair {
+                        plugin: Plugin::Attributes(Attributes {
+                            attribute_list: vec![Attribute {
+                                key: "Order Status".to_string(),
+                                value: "Placed".to_string(),
+                            }],
+                        }),
+                        authority: None,
+                    }])
+                    .instruction()],
+                &[&seller, &asset],
+            )
+            .await
+            .unwrap();
+            products.push(asset.pubkey());
+        }
+        (collection.pubkey(), products)
+    }
+
+    /// Registers and activates a listing for the seller's collection.
+    pub async fn register_product_listing(&mut self, product_listing_id: &Pubkey) {
+        self.register_listing(product_listing_id, None).await;
+    }
+
+    /// Registers and activates a listing paid in `payment_mint`.
+    pub async fn register_token_listing(
+        &mut self,
+        product_listing_id: &Pubkey,
+        payment_mint: &Pubkey,
+    ) {
+        self.register_listing(product_listing_id, Some(*payment_mint))
+            .await;
+    }
+
+    async fn register_listing(
+        &mut self,
+        product_listing_id: &Pubkey,
+        payment_mint: Option<Pubkey>,
+    ) {
+        let seller = self.seller.insecure_clone();
+        let product_listing = pda::product_listing_address(product_listing_id);
+        self.process(
+            &[
+                ix(
+                    accounts::RegisterProductListing {
+                        config: pda::config_address(),
+                        product_listing,
+                        payment_mint,
+                        owner: seller.pubkey(),
+                        system_program: system_program::id(),
+                    },
+                    instruction::RegisterProductListing {
+                        id: *product_listing_id,
+                        origin: Origin::Clickcrate,
+                        placement_type: PlacementType::Digitalreplica,
+                        product_category: ProductCategory::Clothing,
+                        manager: seller.pubkey(),
+                        order_manager: Origin::Clickcrate,
+                    },
+                ),
+                ix(
+                    accounts::ActivateProductListing {
+                        product_listing,
+                        authority: seller.pubkey(),
+                    },
+                    instruction::ActivateProductListing {},
+                ),
+            ],
+            &[&seller],
+        )
+        .await
+        .unwrap();
+    }
+}
+
+pub fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Checks a transaction failed with `expected`, either a `ClickCrateErrors`
+/// variant or one of Anchor's own error codes.
+#[track_caller]
+pub fn assert_program_error(result: Result<(), BanksClientError>, expected: impl Into<u32>) {
+    let expected = expected.into();
+    let err = result.expect_err("transaction should have failed").unwrap();
+    match err {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(code, expected, "unexpected error code")
+        }
+        err => panic!("expected error code {expected}, got {err:?}"),
+    }
+}
@@ -0,0 +1,196 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::{AnchorSerialize, Discriminator};
+use clickcrate_client::{builders, pda};
+use clickcrate_program::{
+    errors::ClickCrateErrors,
+    state::{
+        ClickCrateState, ClickCrateStateV0, OrderOracle, OrderOracleV0, OrderStatus,
+        ProductListingState, ProductListingStateV0,
+    },
+    ID,
+};
+use common::{assert_program_error, TestEnv, PRICE};
+use solana_sdk::{account::Account, rent::Rent, signature::Signer};
+
+/// An account of type `T` as it was written before layouts were versioned.
+fn legacy_account<T: Discriminator>(legacy: &impl AnchorSerialize, space: usize) -> Account {
+    let mut data = T::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut data).unwrap();
+    data.resize(space, 0);
+    Account {
+        lamports: Rent::default().minimum_balance(space),
+        data,
+        owner: ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+#[tokio::test]
+async fn migrates_legacy_accounts_in_place() {
+    let mut env = TestEnv::start().await;
+    let seller = env.seller.insecure_clone();
+    let clickcrate_id = env.register_clickcrate().await;
+    let clickcrate_address = pda::clickcrate_address(&clickcrate_id);
+    let clickcrate: ClickCrateState = env.account(&clickcrate_address).await;
+
+    let legacy = ClickCrateStateV0 {
+        id: clickcrate.id,
+        owner: clickcrate.owner,
+        manager: clickcrate.manager,
+        eligible_placement_type: clickcrate.eligible_placement_type.clone(),
+        eligible_product_category: clickcrate.eligible_product_categories[0].clone(),
+        product: Some(clickcrate_id),
+        is_active: clickcrate.is_active,
+    };
+    env.context.set_account(
+        &clickcrate_address,
+        &legacy_account::<ClickCrateState>(&legacy, ClickCrateStateV0::SPACE).into(),
+    );
+
+    let migrate = builders::migrate_clickcrate(&clickcrate_id, &seller.pubkey());
+    env.process(std::slice::from_ref(&migrate), &[&seller])
+        .await
+        .unwrap();
+
+    let migrated: ClickCrateState = env.account(&clickcrate_address).await;
+    assert_eq!(migrated.version, ClickCrateState::VERSION);
+    assert_eq!(migrated.id, clickcrate.id);
+    assert_eq!(migrated.owner, clickcrate.owner);
+    assert_eq!(migrated.products, vec![clickcrate_id]);
+    assert!(migrated.eligible_product_categories == clickcrate.eligible_product_categories);
+    assert_eq!(migrated.is_active, clickcrate.is_active);
+    let account = env
+        .context
+        .banks_client
+        .get_account(clickcrate_address)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data.len(), 8 + ClickCrateState::size_for(1));
+    assert!(account.lamports >= Rent::default().minimum_balance(account.data.len()));
+
+    assert_program_error(
+        env.process(&[migrate], &[&seller]).await,
+        ClickCrateErrors::AccountAlreadyMigrated,
+    );
+}
+
+#[tokio::test]
+async fn removes_migrated_listings_with_legacy_sales() {
+    let mut env = TestEnv::start().await;
+    let seller = env.seller.insecure_clone();
+    let clickcrate_id = env.register_clickcrate().await;
+    let (listing_id, products) = env.create_listing_collection(3).await;
+    env.register_product_listing(&listing_id).await;
+    env.process(
+        &builders::place_products(
+            &listing_id,
+            &clickcrate_id,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            &products,
+            PRICE,
+            None,
+        ),
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    // Two products were sold before orders were recorded, one of them still
+    // pending, and the seller kept both along with the payments in the vault
+    let listing_address = pda::product_listing_address(&listing_id);
+    let listing: ProductListingState = env.account(&listing_address).await;
+    let legacy = ProductListingStateV0 {
+        id: listing.id,
+        origin: listing.origin.clone(),
+        owner: listing.owner,
+        manager: listing.manager,
+        placement_type: listing.placement_type.clone(),
+        product_category: listing.product_category.clone(),
+        in_stock: 1,
+        sold: 2,
+        clickcrate_pos: listing.clickcrate_pos,
+        is_active: listing.is_active,
+        price: listing.price,
+        vault: listing.vault,
+        order_manager: listing.order_manager.clone(),
+    };
+    env.context.set_account(
+        &listing_address,
+        &legacy_account::<ProductListingState>(&legacy, ProductListingStateV0::SPACE).into(),
+    );
+    for (product, order_status) in [
+        (products[1], OrderStatus::Pending),
+        (products[2], OrderStatus::Completed),
+    ] {
+        let oracle_address = pda::oracle_address(&product);
+        let oracle: OrderOracle = env.account(&oracle_address).await;
+        let legacy = OrderOracleV0 {
+            order_status,
+            order_manager: oracle.order_manager,
+            validation: oracle.validation,
+            bump: oracle.bump,
+        };
+        env.context.set_account(
+            &oracle_address,
+            &legacy_account::<OrderOracle>(&legacy, OrderOracleV0::SPACE).into(),
+        );
+    }
+    let vault_address = pda::vault_address(&listing_id);
+    let mut vault = env
+        .context
+        .banks_client
+        .get_account(vault_address)
+        .await
+        .unwrap()
+        .unwrap();
+    vault.lamports += 2 * PRICE;
+    env.context.set_account(&vault_address, &vault.into());
+
+    env.process(
+        &[
+            builders::migrate_product_listing(&listing_id, &seller.pubkey()),
+            builders::migrate_oracle(&products[1], &seller.pubkey()),
+            builders::migrate_oracle(&products[2], &seller.pubkey()),
+        ],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+    for product in &products[1..] {
+        let oracle: OrderOracle = env.account(&pda::oracle_address(product)).await;
+        assert!(oracle.order_status == OrderStatus::Completed);
+    }
+
+    // The legacy sales are released with the unsold product and their payments swept
+    let listing: ProductListingState = env.account(&listing_address).await;
+    let clickcrate: ClickCrateState = env.account(&pda::clickcrate_address(&clickcrate_id)).await;
+    env.process(
+        &builders::remove_products(
+            &listing,
+            &clickcrate,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            &products,
+            None,
+        ),
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    let listing: ProductListingState = env.account(&listing_address).await;
+    assert_eq!(listing.in_stock, 0);
+    assert_eq!(listing.sold, 2);
+    assert_eq!(listing.seller_proceeds, 2 * PRICE);
+    assert_eq!(listing.clickcrate_pos, None);
+    assert!(!env.exists(&vault_address).await);
+    for product in &products {
+        assert_eq!(env.asset_owner(product).await, seller.pubkey());
+    }
+}
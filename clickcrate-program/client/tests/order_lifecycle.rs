@@ -0,0 +1,853 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use clickcrate_client::{builders, pda};
+use clickcrate_program::{
+    accounts,
+    constants::MAX_STOCKING_BATCH,
+    errors::ClickCrateErrors,
+    instruction,
+    state::{
+        ClickCrateState, Order, OrderOracle, OrderStatus, ProductListingState, ProgramConfig,
+        Settlement, StockingStatus,
+    },
+};
+use common::{assert_program_error, ix, TestEnv, PRICE};
+use solana_sdk::{
+    instruction::AccountMeta,
+    signature::{Keypair, Signer},
+    system_program,
+};
+
+#[tokio::test]
+async fn sells_delivers_and_unstocks_a_listing() {
+    let mut env = TestEnv::start().await;
+    let seller = env.seller.insecure_clone();
+    let clickcrate_id = env.register_clickcrate().await;
+    let (listing_id, products) = env.create_listing_collection(2).await;
+    env.register_product_listing(&listing_id).await;
+    let listing_address = pda::product_listing_address(&listing_id);
+
+    // Oracles can be created up front, placement creates whichever are missing
+    env.process(
+        &[ix(
+            accounts::InitializeOracle {
+                product_listing: listing_address,
+                product: products[0],
+                oracle: pda::oracle_address(&products[0]),
+                payer: seller.pubkey(),
+                system_program: system_program::id(),
+            },
+            instruction::InitializeOracle {
+                product_listing_id: listing_id,
+                product_id: products[0],
+            },
+        )],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    env.process(
+        &builders::place_products(
+            &listing_id,
+            &clickcrate_id,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            &products,
+            PRICE,
+            None,
+        ),
+        &[&seller],
+    )
+    .await
+    .unwrap();
+    let listing: ProductListingState = env.account(&listing_address).await;
+    assert_eq!(listing.in_stock, 2);
+    assert_eq!(listing.clickcrate_pos, Some(clickcrate_id));
+    for product in &products {
+        let oracle: OrderOracle = env.account(&pda::oracle_address(product)).await;
+        assert!(oracle.order_status == OrderStatus::Placed);
+    }
+
+    let buyer = env.funded_keypair(2 * PRICE).await;
+    let vault = pda::vault_address(&listing_id);
+    let vault_balance = env.balance(&vault).await;
+    env.process(
+        &[builders::make_purchase(
+            &listing,
+            &clickcrate_id,
+            &products[0],
+            &buyer.pubkey(),
+            1,
+            None,
+        )],
+        &[&buyer, &seller],
+    )
+    .await
+    .unwrap();
+    assert_eq!(env.balance(&vault).await, vault_balance + PRICE);
+    let order_address = pda::order_address(&listing_id, 0);
+    let order: Order = env.account(&order_address).await;
+    assert!(order.status == OrderStatus::Pending);
+    assert_eq!(order.buyer, buyer.pubkey());
+
+    // Orders can't skip ahead or be moved by a stranger
+    assert_program_error(
+        env.process(
+            &[builders::update_order_status(
+                &order,
+                &seller.pubkey(),
+                OrderStatus::Delivered,
+            )],
+            &[&seller],
+        )
+        .await,
+        ClickCrateErrors::InvalidStatusTransition,
+    );
+    let stranger = Keypair::new();
+    assert_program_error(
+        env.process(
+            &[builders::update_order_status(
+                &order,
+                &stranger.pubkey(),
+                OrderStatus::Confirmed,
+            )],
+            &[&stranger],
+        )
+        .await,
+        ClickCrateErrors::UnauthorizedUpdate,
+    );
+
+    for status in [OrderStatus::Confirmed, OrderStatus::Fulfilled] {
+        env.process(
+            &[builders::update_order_status(
+                &order,
+                &seller.pubkey(),
+                status,
+            )],
+            &[&seller],
+        )
+        .await
+        .unwrap();
+    }
+
+    // A sold product can't be pulled while its order is in flight
+    let listing: ProductListingState = env.account(&listing_address).await;
+    let clickcrate: ClickCrateState = env.account(&pda::clickcrate_address(&clickcrate_id)).await;
+    assert_program_error(
+        env.process(
+            &builders::remove_products(
+                &listing,
+                &clickcrate,
+                &seller.pubkey(),
+                &seller.pubkey(),
+                &products[..1],
+                None,
+            ),
+            &[&seller],
+        )
+        .await,
+        ClickCrateErrors::OrdersInProgress,
+    );
+
+    env.process(
+        &[builders::update_order_status(
+            &order,
+            &seller.pubkey(),
+            OrderStatus::Delivered,
+        )],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    // Funds stay in escrow until the buyer confirms or the dispute window ends
+    let config: ProgramConfig = env.account(&pda::config_address()).await;
+    let order: Order = env.account(&order_address).await;
+    let complete_order = builders::complete_order(
+        &order,
+        &listing,
+        &clickcrate,
+        &config,
+        &seller.pubkey(),
+        None,
+    );
+    assert_program_error(
+        env.process(std::slice::from_ref(&complete_order), &[&seller])
+            .await,
+        ClickCrateErrors::DisputeWindowOpen,
+    );
+
+    env.process(&[builders::confirm_delivery(&order)], &[&buyer])
+        .await
+        .unwrap();
+    let seller_balance = env.balance(&seller.pubkey()).await;
+    env.process(&[complete_order], &[&seller]).await.unwrap();
+
+    let order: Order = env.account(&order_address).await;
+    assert!(order.status == OrderStatus::Completed);
+    assert!(order.settlement == Settlement::PaidOut);
+    assert_eq!(env.asset_owner(&products[0]).await, buyer.pubkey());
+    // The seller is also the treasury and the ClickCrate owner here
+    assert!(env.balance(&seller.pubkey()).await > seller_balance);
+
+    // Oracles outlive their sale, but not a placement
+    let listing: ProductListingState = env.account(&listing_address).await;
+    assert_program_error(
+        env.process(
+            &[builders::close_oracle(
+                &listing_id,
+                &seller.pubkey(),
+                &products[1],
+            )],
+            &[&seller],
+        )
+        .await,
+        ClickCrateErrors::ProductAlreadyPlaced,
+    );
+    env.process(
+        &[builders::close_oracle(
+            &listing_id,
+            &seller.pubkey(),
+            &products[0],
+        )],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+    assert!(!env.exists(&pda::oracle_address(&products[0])).await);
+
+    // The delivered product is skipped and the unsold one handed back
+    assert_eq!(listing.delivered, 1);
+    env.process(
+        &builders::remove_products(
+            &listing,
+            &clickcrate,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            &products,
+            None,
+        ),
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    let listing: ProductListingState = env.account(&listing_address).await;
+    assert_eq!(listing.in_stock, 0);
+    assert_eq!(listing.clickcrate_pos, None);
+    assert_eq!(listing.vault, None);
+    assert!(!env.exists(&vault).await);
+    assert_eq!(env.asset_owner(&products[1]).await, seller.pubkey());
+    let clickcrate: ClickCrateState = env.account(&pda::clickcrate_address(&clickcrate_id)).await;
+    assert!(clickcrate.products.is_empty());
+
+    // Both accounts can be deregistered once nothing is placed or owed
+    assert_eq!(listing.open_orders, 0);
+    env.process(
+        &[
+            builders::close_product_listing(&listing),
+            builders::close_clickcrate(&clickcrate),
+        ],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+    assert!(!env.exists(&listing_address).await);
+    assert!(!env.exists(&pda::clickcrate_address(&clickcrate_id)).await);
+
+    // Oracles go through the collection, so none is stranded by the listing closing
+    let stranger = env.funded_keypair(PRICE).await;
+    let close_oracle = builders::close_oracle(&listing_id, &seller.pubkey(), &products[1]);
+    assert_program_error(
+        env.process(
+            &[builders::close_oracle(
+                &listing_id,
+                &stranger.pubkey(),
+                &products[1],
+            )],
+            &[&stranger],
+        )
+        .await,
+        ClickCrateErrors::UnauthorizedClose,
+    );
+    env.process(&[close_oracle], &[&seller]).await.unwrap();
+    assert!(!env.exists(&pda::oracle_address(&products[1])).await);
+}
+
+#[tokio::test]
+async fn refunds_cancelled_orders_before_unstocking() {
+    let mut env = TestEnv::start().await;
+    let seller = env.seller.insecure_clone();
+    let clickcrate_id = env.register_clickcrate().await;
+    let (listing_id, products) = env.create_listing_collection(1).await;
+    env.register_product_listing(&listing_id).await;
+    let listing_address = pda::product_listing_address(&listing_id);
+    env.process(
+        &builders::place_products(
+            &listing_id,
+            &clickcrate_id,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            &products,
+            PRICE,
+            None,
+        ),
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    let buyer = env.funded_keypair(2 * PRICE).await;
+    let listing: ProductListingState = env.account(&listing_address).await;
+    env.process(
+        &[builders::make_purchase(
+            &listing,
+            &clickcrate_id,
+            &products[0],
+            &buyer.pubkey(),
+            1,
+            None,
+        )],
+        &[&buyer, &seller],
+    )
+    .await
+    .unwrap();
+    let order_address = pda::order_address(&listing_id, 0);
+    let order: Order = env.account(&order_address).await;
+    env.process(
+        &[builders::update_order_status(
+            &order,
+            &seller.pubkey(),
+            OrderStatus::Cancelled,
+        )],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    // The buyer's payment is still in the vault, so it can't be swept yet
+    let listing: ProductListingState = env.account(&listing_address).await;
+    let clickcrate: ClickCrateState = env.account(&pda::clickcrate_address(&clickcrate_id)).await;
+    let remove_products = builders::remove_products(
+        &listing,
+        &clickcrate,
+        &seller.pubkey(),
+        &seller.pubkey(),
+        &products,
+        None,
+    );
+    assert_program_error(
+        env.process(&remove_products, &[&seller]).await,
+        ClickCrateErrors::OrdersInProgress,
+    );
+
+    let buyer_balance = env.balance(&buyer.pubkey()).await;
+    env.process(
+        &[builders::refund_order(&order, &buyer.pubkey(), None)],
+        &[&buyer],
+    )
+    .await
+    .unwrap();
+    let order: Order = env.account(&order_address).await;
+    assert!(order.settlement == Settlement::Refunded);
+    assert_eq!(env.balance(&buyer.pubkey()).await, buyer_balance + PRICE);
+
+    env.process(&remove_products, &[&seller]).await.unwrap();
+    let listing: ProductListingState = env.account(&listing_address).await;
+    assert_eq!(listing.open_orders, 0);
+    assert_eq!(listing.clickcrate_pos, None);
+    assert!(!env.exists(&pda::vault_address(&listing_id)).await);
+    assert_eq!(env.asset_owner(&products[0]).await, seller.pubkey());
+}
+
+#[tokio::test]
+async fn rejects_purchases_of_unplaced_or_sold_products() {
+    let mut env = TestEnv::start().await;
+    let seller = env.seller.insecure_clone();
+    let clickcrate_id = env.register_clickcrate().await;
+    let (listing_id, products) = env.create_listing_collection(1).await;
+    env.register_product_listing(&listing_id).await;
+    let listing_address = pda::product_listing_address(&listing_id);
+    let buyer = env.funded_keypair(2 * PRICE).await;
+    let other_buyer = env.funded_keypair(2 * PRICE).await;
+
+    // Without a placement there is no oracle to buy through
+    let listing: ProductListingState = env.account(&listing_address).await;
+    assert_program_error(
+        env.process(
+            &[builders::make_purchase(
+                &listing,
+                &clickcrate_id,
+                &products[0],
+                &buyer.pubkey(),
+                1,
+                None,
+            )],
+            &[&buyer, &seller],
+        )
+        .await,
+        ErrorCode::AccountNotInitialized,
+    );
+
+    env.process(
+        &builders::place_products(
+            &listing_id,
+            &clickcrate_id,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            &products,
+            PRICE,
+            None,
+        ),
+        &[&seller],
+    )
+    .await
+    .unwrap();
+    let listing: ProductListingState = env.account(&listing_address).await;
+
+    // An order delivers exactly the one asset it was placed for
+    assert_program_error(
+        env.process(
+            &[builders::make_purchase(
+                &listing,
+                &clickcrate_id,
+                &products[0],
+                &buyer.pubkey(),
+                2,
+                None,
+            )],
+            &[&buyer, &seller],
+        )
+        .await,
+        ClickCrateErrors::InvalidQuantity,
+    );
+
+    env.process(
+        &[builders::make_purchase(
+            &listing,
+            &clickcrate_id,
+            &products[0],
+            &buyer.pubkey(),
+            1,
+            None,
+        )],
+        &[&buyer, &seller],
+    )
+    .await
+    .unwrap();
+
+    // The oracle left `Placed` with the first sale
+    let listing: ProductListingState = env.account(&listing_address).await;
+    assert_program_error(
+        env.process(
+            &[builders::make_purchase(
+                &listing,
+                &clickcrate_id,
+                &products[0],
+                &other_buyer.pubkey(),
+                1,
+                None,
+            )],
+            &[&other_buyer, &seller],
+        )
+        .await,
+        ClickCrateErrors::ProductNotPlaced,
+    );
+    assert_eq!(listing.sold, 1);
+    assert!(!env.exists(&pda::order_address(&listing_id, 1)).await);
+}
+
+#[tokio::test]
+async fn rejects_assets_from_other_collections() {
+    let mut env = TestEnv::start().await;
+    let seller = env.seller.insecure_clone();
+    let clickcrate_id = env.register_clickcrate().await;
+    let (listing_id, products) = env.create_listing_collection(1).await;
+    env.register_product_listing(&listing_id).await;
+    env.process(
+        &builders::place_products(
+            &listing_id,
+            &clickcrate_id,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            &products,
+            PRICE,
+            None,
+        ),
+        &[&seller],
+    )
+    .await
+    .unwrap();
+    let (other_collection, other_products) = env.create_listing_collection(1).await;
+
+    // Restocking through another collection would count its assets as this listing's
+    let mut add_stock = builders::add_stock(
+        &listing_id,
+        &clickcrate_id,
+        &seller.pubkey(),
+        &other_products,
+    );
+    for meta in &mut add_stock[0].accounts {
+        if meta.pubkey == listing_id {
+            meta.pubkey = other_collection;
+        }
+    }
+    assert_program_error(
+        env.process(&add_stock, &[&seller]).await,
+        ErrorCode::ConstraintAddress,
+    );
+    let listing: ProductListingState = env
+        .account(&pda::product_listing_address(&listing_id))
+        .await;
+    assert_eq!(listing.in_stock, 1);
+
+    // Placements only take assets of the listing's own collection
+    env.register_product_listing(&other_collection).await;
+    assert_program_error(
+        env.process(
+            &builders::place_products(
+                &other_collection,
+                &clickcrate_id,
+                &seller.pubkey(),
+                &seller.pubkey(),
+                &products,
+                PRICE,
+                None,
+            ),
+            &[&seller],
+        )
+        .await,
+        ClickCrateErrors::InvalidProductAccount,
+    );
+
+    // Nor can a product placed under one listing be bought through another
+    env.process(
+        &builders::place_products(
+            &other_collection,
+            &clickcrate_id,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            &other_products,
+            PRICE,
+            None,
+        ),
+        &[&seller],
+    )
+    .await
+    .unwrap();
+    let other_listing: ProductListingState = env
+        .account(&pda::product_listing_address(&other_collection))
+        .await;
+    let buyer = env.funded_keypair(2 * PRICE).await;
+    let mut make_purchase = builders::make_purchase(
+        &other_listing,
+        &clickcrate_id,
+        &products[0],
+        &buyer.pubkey(),
+        1,
+        None,
+    );
+    for meta in &mut make_purchase.accounts {
+        if meta.pubkey == other_collection {
+            meta.pubkey = listing_id;
+        }
+    }
+    assert_program_error(
+        env.process(&[make_purchase], &[&buyer, &seller]).await,
+        ErrorCode::ConstraintAddress,
+    );
+}
+
+#[tokio::test]
+async fn aborts_a_placement_that_cannot_finish() {
+    let mut env = TestEnv::start().await;
+    let seller = env.seller.insecure_clone();
+    let clickcrate_id = env.register_clickcrate().await;
+    let (listing_id, products) = env.create_listing_collection(3).await;
+    env.register_product_listing(&listing_id).await;
+    let listing_address = pda::product_listing_address(&listing_id);
+
+    // The last asset never gets placed, so the placement stays open
+    env.process(
+        &builders::place_products(
+            &listing_id,
+            &clickcrate_id,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            &products[..2],
+            PRICE,
+            None,
+        ),
+        &[&seller],
+    )
+    .await
+    .unwrap();
+    let listing: ProductListingState = env.account(&listing_address).await;
+    assert!(listing.stocking == StockingStatus::Placing);
+    assert_eq!(listing.stocking_remaining(), 1);
+
+    let stranger = env.funded_keypair(PRICE).await;
+    assert_program_error(
+        env.process(
+            &[builders::abort_stocking(&listing_id, &stranger.pubkey())],
+            &[&stranger],
+        )
+        .await,
+        ClickCrateErrors::UnauthorizedUpdate,
+    );
+    env.process(
+        &[builders::abort_stocking(&listing_id, &seller.pubkey())],
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    let listing: ProductListingState = env.account(&listing_address).await;
+    assert!(listing.stocking == StockingStatus::Idle);
+    assert_eq!(listing.in_stock, 2);
+    assert_eq!(listing.clickcrate_pos, Some(clickcrate_id));
+    assert_program_error(
+        env.process(
+            &[builders::abort_stocking(&listing_id, &seller.pubkey())],
+            &[&seller],
+        )
+        .await,
+        ClickCrateErrors::InvalidStockingRequest,
+    );
+}
+
+#[tokio::test]
+async fn clickcrate_managers_host_placements() {
+    let mut env = TestEnv::start().await;
+    let seller = env.seller.insecure_clone();
+    let host = env.funded_keypair(PRICE).await;
+    let manager = env.funded_keypair(PRICE).await;
+    let clickcrate_id = env
+        .register_managed_clickcrate(&host, &manager.pubkey())
+        .await;
+    let clickcrate_address = pda::clickcrate_address(&clickcrate_id);
+    let (listing_id, products) = env.create_listing_collection(1).await;
+    env.register_product_listing(&listing_id).await;
+    let empty_clickcrate_rent = env.balance(&clickcrate_address).await;
+
+    let place_products = |clickcrate_authority: &Keypair| {
+        builders::place_products(
+            &listing_id,
+            &clickcrate_id,
+            &seller.pubkey(),
+            &clickcrate_authority.pubkey(),
+            &products,
+            PRICE,
+            None,
+        )
+    };
+    let stranger = env.funded_keypair(PRICE).await;
+    assert_program_error(
+        env.process(&place_products(&stranger), &[&seller, &stranger])
+            .await,
+        ClickCrateErrors::UnauthorizedUpdate,
+    );
+    env.process(&place_products(&manager), &[&seller, &manager])
+        .await
+        .unwrap();
+    assert!(env.balance(&clickcrate_address).await > empty_clickcrate_rent);
+
+    // The seller paid to grow the ClickCrate, so removal refunds them, not its owner
+    let listing: ProductListingState = env
+        .account(&pda::product_listing_address(&listing_id))
+        .await;
+    let clickcrate: ClickCrateState = env.account(&clickcrate_address).await;
+    let host_balance = env.balance(&host.pubkey()).await;
+    env.process(
+        &builders::remove_products(
+            &listing,
+            &clickcrate,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            &products,
+            None,
+        ),
+        &[&seller],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        env.balance(&clickcrate_address).await,
+        empty_clickcrate_rent
+    );
+    assert_eq!(env.balance(&host.pubkey()).await, host_balance);
+}
+
+#[tokio::test]
+async fn paused_clickcrates_take_no_further_batches() {
+    let mut env = TestEnv::start().await;
+    let seller = env.seller.insecure_clone();
+    let clickcrate_id = env.register_clickcrate().await;
+    let (listing_id, products) = env.create_listing_collection(2).await;
+    env.register_product_listing(&listing_id).await;
+    env.process(
+        &builders::place_products(
+            &listing_id,
+            &clickcrate_id,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            &products[..1],
+            PRICE,
+            None,
+        ),
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    let set_clickcrate_paused = |paused| {
+        ix(
+            accounts::SetClickCratePaused {
+                config: pda::config_address(),
+                clickcrate: pda::clickcrate_address(&clickcrate_id),
+                admin: seller.pubkey(),
+            },
+            instruction::SetClickcratePaused { paused },
+        )
+    };
+    let mut resume_placement = ix(
+        accounts::ResumePlacement {
+            config: pda::config_address(),
+            product_listing: pda::product_listing_address(&listing_id),
+            clickcrate: pda::clickcrate_address(&clickcrate_id),
+            listing_collection: listing_id,
+            authority: seller.pubkey(),
+            core_program: mpl_core::ID,
+            system_program: system_program::id(),
+        },
+        instruction::ResumePlacement {
+            product_listing_id: listing_id,
+        },
+    );
+    resume_placement.accounts.extend([
+        AccountMeta::new(products[1], false),
+        AccountMeta::new(pda::oracle_address(&products[1]), false),
+    ]);
+
+    // Pausing the ClickCrate holds back the rest of the placement
+    env.process(&[set_clickcrate_paused(true)], &[&seller])
+        .await
+        .unwrap();
+    assert_program_error(
+        env.process(std::slice::from_ref(&resume_placement), &[&seller])
+            .await,
+        ClickCrateErrors::ProgramPaused,
+    );
+
+    env.process(&[set_clickcrate_paused(false)], &[&seller])
+        .await
+        .unwrap();
+    env.process(&[resume_placement], &[&seller]).await.unwrap();
+    let listing: ProductListingState = env
+        .account(&pda::product_listing_address(&listing_id))
+        .await;
+    assert!(listing.stocking == StockingStatus::Idle);
+    assert_eq!(listing.in_stock, 2);
+}
+
+#[tokio::test]
+async fn closes_the_vault_token_account_on_removal() {
+    let mut env = TestEnv::start().await;
+    let seller = env.seller.insecure_clone();
+    let clickcrate_id = env.register_clickcrate().await;
+    let (listing_id, products) = env.create_listing_collection(1).await;
+    let payment = env.create_payment_mint().await;
+    env.register_token_listing(&listing_id, &payment.mint).await;
+    env.process(
+        &builders::place_products(
+            &listing_id,
+            &clickcrate_id,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            &products,
+            PRICE,
+            Some(payment),
+        ),
+        &[&seller],
+    )
+    .await
+    .unwrap();
+    let vault_token_account =
+        pda::vault_token_address(&listing_id, &payment.mint, &payment.token_program);
+    assert!(env.exists(&vault_token_account).await);
+
+    let listing: ProductListingState = env
+        .account(&pda::product_listing_address(&listing_id))
+        .await;
+    let clickcrate: ClickCrateState = env.account(&pda::clickcrate_address(&clickcrate_id)).await;
+    env.process(
+        &builders::remove_products(
+            &listing,
+            &clickcrate,
+            &seller.pubkey(),
+            &seller.pubkey(),
+            &products,
+            Some(payment),
+        ),
+        &[&seller],
+    )
+    .await
+    .unwrap();
+
+    // Both the vault and its token account are closed, nothing is left behind
+    assert!(!env.exists(&vault_token_account).await);
+    assert!(!env.exists(&pda::vault_address(&listing_id)).await);
+}
+
+#[tokio::test]
+async fn places_and_removes_full_batches() {
+    let mut env = TestEnv::start().await;
+    let seller = env.seller.insecure_clone();
+    let manager = env.funded_keypair(PRICE).await;
+    let clickcrate_id = env
+        .register_managed_clickcrate(&seller, &manager.pubkey())
+        .await;
+    let (listing_id, products) = env.create_listing_collection(MAX_STOCKING_BATCH + 1).await;
+    let payment = env.create_payment_mint().await;
+    env.register_token_listing(&listing_id, &payment.mint).await;
+
+    // The largest accounts lists: token payments, a ClickCrate manager co-signing
+    // and a separate fee payer, each batch sent in a transaction of its own
+    let place_products = builders::place_products(
+        &listing_id,
+        &clickcrate_id,
+        &seller.pubkey(),
+        &manager.pubkey(),
+        &products,
+        PRICE,
+        Some(payment),
+    );
+    assert_eq!(place_products.len(), 2);
+    for batch in place_products {
+        env.process(&[batch], &[&seller, &manager]).await.unwrap();
+    }
+    let listing: ProductListingState = env
+        .account(&pda::product_listing_address(&listing_id))
+        .await;
+    assert!(listing.stocking == StockingStatus::Idle);
+    assert_eq!(listing.in_stock, products.len() as u64);
+
+    let clickcrate: ClickCrateState = env.account(&pda::clickcrate_address(&clickcrate_id)).await;
+    let remove_products = builders::remove_products(
+        &listing,
+        &clickcrate,
+        &seller.pubkey(),
+        &seller.pubkey(),
+        &products,
+        Some(payment),
+    );
+    assert_eq!(remove_products.len(), 2);
+    for batch in remove_products {
+        env.process(&[batch], &[&seller]).await.unwrap();
+    }
+    assert!(!env.exists(&pda::vault_address(&listing_id)).await);
+}
@@ -0,0 +1,129 @@
+use anchor_lang::prelude::Pubkey;
+use clickcrate_client::builders::{self, TokenPayment};
+use clickcrate_program::{
+    constants::MAX_STOCKING_BATCH,
+    state::{
+        ClickCrateState, Origin, PlacementType, ProductCategory, ProductListingState,
+        StockingStatus,
+    },
+};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::Instruction,
+    packet::PACKET_DATA_SIZE,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+const PAYMENT: TokenPayment = TokenPayment {
+    mint: Pubkey::new_from_array([9; 32]),
+    token_program: anchor_spl::token_2022::ID,
+};
+
+/// Size of `instruction` sent with a compute budget by a separate fee payer,
+/// signed by `signers` as well.
+fn transaction_size(instruction: &Instruction, signers: &[&Keypair]) -> usize {
+    let payer = Keypair::new();
+    let mut all_signers = vec![&payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_limit(1_400_000),
+            instruction.clone(),
+        ],
+        Some(&payer.pubkey()),
+        &all_signers,
+        Hash::default(),
+    );
+    bincode::serialize(&transaction).unwrap().len()
+}
+
+fn products() -> Vec<Pubkey> {
+    (0..MAX_STOCKING_BATCH)
+        .map(|_| Pubkey::new_unique())
+        .collect()
+}
+
+#[test]
+fn full_placement_batches_fit_in_a_transaction() {
+    let seller = Keypair::new();
+    let manager = Keypair::new();
+    let place_products = builders::place_products(
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &seller.pubkey(),
+        &manager.pubkey(),
+        &products(),
+        1,
+        Some(PAYMENT),
+    );
+    assert_eq!(place_products.len(), 1);
+    assert!(transaction_size(&place_products[0], &[&seller, &manager]) <= PACKET_DATA_SIZE);
+
+    let add_stock = builders::add_stock(
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        &seller.pubkey(),
+        &products(),
+    );
+    assert_eq!(add_stock.len(), 1);
+    assert!(transaction_size(&add_stock[0], &[&seller]) <= PACKET_DATA_SIZE);
+}
+
+#[test]
+fn full_removal_batches_fit_in_a_transaction() {
+    let seller = Keypair::new();
+    let product_listing = ProductListingState {
+        version: ProductListingState::VERSION,
+        id: Pubkey::new_unique(),
+        origin: Origin::Clickcrate,
+        owner: Pubkey::new_unique(),
+        manager: Pubkey::new_unique(),
+        placement_type: PlacementType::Digitalreplica,
+        product_category: ProductCategory::Clothing,
+        in_stock: MAX_STOCKING_BATCH as u64,
+        sold: 0,
+        clickcrate_pos: Some(Pubkey::new_unique()),
+        is_active: true,
+        price: Some(1),
+        vault: Some(Pubkey::new_unique()),
+        order_manager: Origin::Clickcrate,
+        payment_mint: Some(PAYMENT.mint),
+        fees_paid: 0,
+        seller_proceeds: 0,
+        commissions_paid: 0,
+        order_count: 0,
+        delivered: 0,
+        stocking: StockingStatus::Idle,
+        stocking_cursor: 0,
+        stocking_target: 0,
+        is_paused: false,
+        open_orders: 0,
+        fee_basis_points: 0,
+        reserved: [0; 64],
+    };
+    let clickcrate = ClickCrateState {
+        version: ClickCrateState::VERSION,
+        id: product_listing.clickcrate_pos.unwrap(),
+        owner: Pubkey::new_unique(),
+        manager: Pubkey::new_unique(),
+        eligible_placement_type: PlacementType::Digitalreplica,
+        eligible_product_categories: vec![ProductCategory::Clothing],
+        products: vec![product_listing.id],
+        is_active: true,
+        commission_basis_points: 0,
+        is_paused: false,
+        reserved: [0; 64],
+    };
+    let remove_products = builders::remove_products(
+        &product_listing,
+        &clickcrate,
+        &Pubkey::new_unique(),
+        &seller.pubkey(),
+        &products(),
+        Some(PAYMENT),
+    );
+    assert_eq!(remove_products.len(), 1);
+    assert!(transaction_size(&remove_products[0], &[&seller]) <= PACKET_DATA_SIZE);
+}
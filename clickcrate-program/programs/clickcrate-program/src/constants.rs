@@ -1,2 +1,14 @@
 pub const MAX_PRODUCT_NAME_LENGTH: usize = 50;
 pub const DEFAULT_FEE_BASIS_POINTS: u16 = 100; // 1%
+pub const MAX_ELIGIBLE_PRODUCT_CATEGORIES: usize = 11;
+pub const MAX_CLICKCRATE_PRODUCTS: usize = 10;
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+// Fees and commissions are capped so that together they never exceed a payout
+pub const MAX_FEE_BASIS_POINTS: u16 = 1_000; // 10%
+pub const MAX_COMMISSION_BASIS_POINTS: u16 = MAX_BASIS_POINTS - MAX_FEE_BASIS_POINTS;
+// `[asset, oracle]` pairs per stocking instruction. Each asset takes three Core
+// CPIs and an oracle account, and a batch must fit in one 1232 byte transaction
+// alongside the token accounts of a token-paid listing and a separate fee payer.
+pub const MAX_STOCKING_BATCH: usize = 5;
+pub const MAX_ALLOWED_ORIGINS: usize = 3;
+pub const DEFAULT_DISPUTE_WINDOW_SECONDS: i64 = 7 * 24 * 60 * 60; // 7 days
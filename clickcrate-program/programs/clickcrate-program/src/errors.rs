@@ -115,4 +115,103 @@ pub enum ClickCrateErrors {
 
     #[msg("Unauthorized close")]
     UnauthorizedClose,
+
+    #[msg("Invalid payment mint")]
+    InvalidPaymentMint,
+
+    #[msg("Invalid token account")]
+    InvalidTokenAccount,
+
+    #[msg("Payment accounts not provided")]
+    MissingPaymentAccounts,
+
+    #[msg("Amount overflow")]
+    AmountOverflow,
+
+    #[msg("Invalid fee basis points")]
+    InvalidFeeBasisPoints,
+
+    #[msg("Invalid commission")]
+    InvalidCommission,
+
+    #[msg("Invalid treasury")]
+    InvalidTreasury,
+
+    #[msg("Unauthorized admin")]
+    UnauthorizedAdmin,
+
+    #[msg("Invalid new owner")]
+    InvalidNewOwner,
+
+    #[msg("A placement or removal is still in progress")]
+    StockingInProgress,
+
+    #[msg("Invalid order status transition")]
+    InvalidStatusTransition,
+
+    #[msg("Invalid dispute window")]
+    InvalidDisputeWindow,
+
+    #[msg("Dispute window has not ended")]
+    DisputeWindowOpen,
+
+    #[msg("Invalid arbiter")]
+    InvalidArbiter,
+
+    #[msg("Order is under dispute")]
+    OrderDisputed,
+
+    #[msg("Order can't be disputed")]
+    DisputeNotAllowed,
+
+    #[msg("Order is not under dispute")]
+    OrderNotDisputed,
+
+    #[msg("Invalid dispute resolution")]
+    InvalidDisputeResolution,
+
+    #[msg("No admin handover pending for this signer")]
+    InvalidPendingAdmin,
+
+    #[msg("Invalid allowed origins")]
+    InvalidAllowedOrigins,
+
+    #[msg("Origin not allowed")]
+    OriginNotAllowed,
+
+    #[msg("Invalid config limit")]
+    InvalidConfigLimit,
+
+    #[msg("Program is paused")]
+    ProgramPaused,
+
+    #[msg("Order is not final")]
+    OrderNotFinal,
+
+    #[msg("Account is already at the current version")]
+    AccountAlreadyMigrated,
+
+    #[msg("Account layout not recognized")]
+    InvalidAccountVersion,
+
+    #[msg("Each order covers exactly one product")]
+    InvalidQuantity,
+
+    #[msg("ClickCrate is full")]
+    ClickCrateFull,
+
+    #[msg("Product already placed")]
+    ProductAlreadyPlaced,
+
+    #[msg("Order already settled")]
+    OrderAlreadySettled,
+
+    #[msg("Order not cancelled")]
+    OrderNotCancelled,
+
+    #[msg("Placement type not eligible for this ClickCrate")]
+    IneligiblePlacementType,
+
+    #[msg("Product category not eligible for this ClickCrate")]
+    IneligibleProductCategory,
 }
@@ -0,0 +1,265 @@
+use crate::state::{OrderStatus, Origin, PlacementType, ProductCategory};
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct ConfigInitialized {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub arbiter: Pubkey,
+    pub fee_basis_points: u16,
+    pub dispute_window_seconds: i64,
+    pub allowed_origins: Vec<Origin>,
+    pub max_clickcrate_products: u8,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub arbiter: Pubkey,
+    pub fee_basis_points: u16,
+    pub dispute_window_seconds: i64,
+    pub allowed_origins: Vec<Origin>,
+    pub max_clickcrate_products: u8,
+}
+
+#[event]
+pub struct AdminProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub previous_admin: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct ClickCrateRegistered {
+    pub id: Pubkey,
+    pub owner: Pubkey,
+    pub manager: Pubkey,
+    pub eligible_placement_type: PlacementType,
+    pub eligible_product_categories: Vec<ProductCategory>,
+    pub commission_basis_points: u16,
+}
+
+#[event]
+pub struct ClickCrateUpdated {
+    pub id: Pubkey,
+    pub authority: Pubkey,
+    pub manager: Pubkey,
+    pub eligible_placement_type: PlacementType,
+    pub eligible_product_categories: Vec<ProductCategory>,
+    pub commission_basis_points: u16,
+}
+
+#[event]
+pub struct ClickCrateActivated {
+    pub id: Pubkey,
+}
+
+#[event]
+pub struct ClickCrateDeactivated {
+    pub id: Pubkey,
+}
+
+#[event]
+pub struct ClickCrateClosed {
+    pub id: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct ClickCrateOwnershipTransferred {
+    pub id: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct ProductListingRegistered {
+    pub id: Pubkey,
+    pub owner: Pubkey,
+    pub manager: Pubkey,
+    pub origin: Origin,
+    pub placement_type: PlacementType,
+    pub product_category: ProductCategory,
+    pub order_manager: Origin,
+    pub payment_mint: Option<Pubkey>,
+}
+
+#[event]
+pub struct ProductListingUpdated {
+    pub id: Pubkey,
+    pub authority: Pubkey,
+    pub manager: Pubkey,
+    pub placement_type: PlacementType,
+    pub product_category: ProductCategory,
+    pub price: u64,
+}
+
+#[event]
+pub struct ProductListingActivated {
+    pub id: Pubkey,
+}
+
+#[event]
+pub struct ProductListingDeactivated {
+    pub id: Pubkey,
+}
+
+#[event]
+pub struct ProductListingClosed {
+    pub id: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct ProductListingOwnershipTransferred {
+    pub id: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct OracleInitialized {
+    pub product_listing_id: Pubkey,
+    pub product_id: Pubkey,
+    pub oracle: Pubkey,
+}
+
+#[event]
+pub struct OracleClosed {
+    pub product_listing_id: Pubkey,
+    pub product_id: Pubkey,
+    pub oracle: Pubkey,
+}
+
+#[event]
+pub struct ProductsPlaced {
+    pub product_listing_id: Pubkey,
+    pub clickcrate_id: Pubkey,
+    pub vault: Pubkey,
+    pub products: Vec<Pubkey>,
+    pub price: u64,
+    pub payment_mint: Option<Pubkey>,
+    pub in_stock: u64,
+    pub remaining: u32,
+}
+
+#[event]
+pub struct StockingAborted {
+    pub product_listing_id: Pubkey,
+    pub in_stock: u64,
+    pub unplaced: u32,
+}
+
+#[event]
+pub struct StockAdded {
+    pub product_listing_id: Pubkey,
+    pub products: Vec<Pubkey>,
+    pub in_stock: u64,
+}
+
+#[event]
+pub struct ProductsRemoved {
+    pub product_listing_id: Pubkey,
+    pub clickcrate_id: Pubkey,
+    pub products: Vec<Pubkey>,
+    pub protocol_fee: u64,
+    pub seller_proceeds: u64,
+    pub in_stock: u64,
+    pub remaining: u32,
+}
+
+#[event]
+pub struct PurchaseMade {
+    pub order: Pubkey,
+    pub order_id: u64,
+    pub product_listing_id: Pubkey,
+    pub clickcrate_id: Pubkey,
+    pub product_id: Pubkey,
+    pub buyer: Pubkey,
+    pub quantity: u64,
+    pub unit_price: u64,
+    pub total_paid: u64,
+    pub payment_mint: Option<Pubkey>,
+}
+
+#[event]
+pub struct OrderStatusChanged {
+    pub order: Pubkey,
+    pub order_id: u64,
+    pub product_listing_id: Pubkey,
+    pub product_id: Pubkey,
+    pub authority: Pubkey,
+    pub previous_status: OrderStatus,
+    pub new_status: OrderStatus,
+    pub release_at: Option<i64>,
+}
+
+#[event]
+pub struct OrderCompleted {
+    pub order: Pubkey,
+    pub order_id: u64,
+    pub product_listing_id: Pubkey,
+    pub clickcrate_id: Pubkey,
+    pub product_id: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub total_paid: u64,
+    pub protocol_fee: u64,
+    pub commission: u64,
+    pub seller_proceeds: u64,
+    pub payment_mint: Option<Pubkey>,
+}
+
+#[event]
+pub struct OrderRefunded {
+    pub order: Pubkey,
+    pub order_id: u64,
+    pub product_listing_id: Pubkey,
+    pub product_id: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub payment_mint: Option<Pubkey>,
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub order: Pubkey,
+    pub order_id: u64,
+    pub product_listing_id: Pubkey,
+    pub product_id: Pubkey,
+    pub buyer: Pubkey,
+    pub previous_status: OrderStatus,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub order: Pubkey,
+    pub order_id: u64,
+    pub product_listing_id: Pubkey,
+    pub product_id: Pubkey,
+    pub arbiter: Pubkey,
+    pub buyer_refund: u64,
+    pub protocol_fee: u64,
+    pub commission: u64,
+    pub seller_proceeds: u64,
+    pub payment_mint: Option<Pubkey>,
+}
+
+#[event]
+pub struct PauseChanged {
+    pub account: Pubkey,
+    pub admin: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub version: u8,
+}
@@ -0,0 +1,39 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::StockingAborted;
+use crate::state::{Action, Managed, ProductListingState, StockingStatus};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(product_listing_id: Pubkey)]
+pub struct AbortStocking<'info> {
+    #[account(
+        mut,
+        seeds = [b"listing".as_ref(), product_listing_id.key().as_ref()],
+        bump,
+        constraint = product_listing.can(&owner.key(), Action::PlaceProducts)
+            @ ClickCrateErrors::UnauthorizedUpdate,
+    )]
+    pub product_listing: Account<'info, ProductListingState>,
+    pub owner: Signer<'info>,
+}
+
+/// Ends a placement that can't be finished, for instance because assets of the
+/// collection left the seller's wallet before they were placed. The listing
+/// keeps the assets already placed and can be restocked with `add_stock`.
+/// Removals aren't aborted, as placed assets stay frozen until they are released.
+pub fn abort_stocking(ctx: Context<AbortStocking>, product_listing_id: Pubkey) -> Result<()> {
+    let product_listing = &mut ctx.accounts.product_listing;
+    require!(
+        product_listing.stocking == StockingStatus::Placing,
+        ClickCrateErrors::InvalidStockingRequest
+    );
+    let unplaced = product_listing.stocking_remaining();
+    product_listing.begin_stocking(StockingStatus::Idle, 0);
+    emit!(StockingAborted {
+        product_listing_id,
+        in_stock: product_listing.in_stock,
+        unplaced,
+    });
+    msg!("Placement aborted with {} assets unplaced", unplaced);
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::AdminTransferred;
+use crate::state::ProgramConfig;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        constraint = config.pending_admin == Some(pending_admin.key())
+            @ ClickCrateErrors::InvalidPendingAdmin,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    pub pending_admin: Signer<'info>,
+}
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let previous_admin = std::mem::replace(&mut config.admin, ctx.accounts.pending_admin.key());
+    config.pending_admin = None;
+    emit!(AdminTransferred {
+        previous_admin,
+        admin: config.admin,
+    });
+    Ok(())
+}
@@ -1,4 +1,6 @@
-use crate::state::ProductListingState;
+use crate::errors::ClickCrateErrors;
+use crate::events::ProductListingActivated;
+use crate::state::{Action, Managed, ProductListingState};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -7,14 +9,18 @@ pub struct ActivateProductListing<'info> {
         mut,
         seeds = [b"listing".as_ref(), product_listing.id.as_ref()],
         bump,
-        has_one = owner
+        constraint = product_listing.can(&authority.key(), Action::SetActive)
+            @ ClickCrateErrors::UnauthorizedUpdate,
     )]
     pub product_listing: Account<'info, ProductListingState>,
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
 }
 
 pub fn activate_product_listing(ctx: Context<ActivateProductListing>) -> Result<()> {
     let product_listing = &mut ctx.accounts.product_listing;
     product_listing.is_active = true;
+    emit!(ProductListingActivated {
+        id: product_listing.id
+    });
     Ok(())
 }
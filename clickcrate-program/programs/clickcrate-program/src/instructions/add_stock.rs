@@ -0,0 +1,128 @@
+use crate::constants::MAX_STOCKING_BATCH;
+use crate::errors::ClickCrateErrors;
+use crate::events::StockAdded;
+use crate::instructions::place_products::{add_product_plugins, StockingAccounts};
+use crate::state::{
+    Action, ClickCrateState, Managed, ProductListingState, ProgramConfig, StockingStatus,
+};
+use crate::Core;
+use anchor_lang::prelude::*;
+use mpl_core::{types::UpdateAuthority, Asset, Collection};
+
+#[derive(Accounts)]
+#[instruction(product_listing_id: Pubkey)]
+pub struct AddStock<'info> {
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(
+        mut,
+        seeds = [b"listing".as_ref(), product_listing_id.key().as_ref()],
+        bump,
+        constraint = product_listing.can(&authority.key(), Action::PlaceProducts)
+            @ ClickCrateErrors::UnauthorizedUpdate,
+    )]
+    pub product_listing: Account<'info, ProductListingState>,
+    #[account(
+        seeds = [b"clickcrate".as_ref(), clickcrate.id.as_ref()],
+        bump,
+        constraint = product_listing.clickcrate_pos == Some(clickcrate.id)
+            @ ClickCrateErrors::ProductNotPlaced,
+    )]
+    pub clickcrate: Account<'info, ClickCrateState>,
+    /// CHECK: This is the Metaplex core collection account, whose address is the listing id
+    #[account(mut, address = product_listing.id)]
+    pub listing_collection: UncheckedAccount<'info>,
+    /// Signs the Core plugin CPIs, so it must also hold authority over the assets
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub core_program: Program<'info, Core>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_stock<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, AddStock<'info>>,
+    product_listing_id: Pubkey,
+) -> Result<()> {
+    let product_listing = &mut ctx.accounts.product_listing;
+    let listing_collection = &ctx.accounts.listing_collection;
+    let product_accounts = ctx.remaining_accounts;
+
+    require!(
+        product_listing.is_active,
+        ClickCrateErrors::ProductListingDeactivated
+    );
+    require!(
+        product_listing.clickcrate_pos.is_some() && product_listing.vault.is_some(),
+        ClickCrateErrors::ProductNotPlaced
+    );
+    require!(
+        product_listing.stocking == StockingStatus::Idle,
+        ClickCrateErrors::StockingInProgress
+    );
+    ctx.accounts
+        .config
+        .require_unpaused(product_listing, Some(&ctx.accounts.clickcrate))?;
+
+    // Measured like a placement. Sold assets may since have been burned by
+    // their buyers, so only the stock still held counts against it.
+    let collection_size = {
+        let collection_data = listing_collection.try_borrow_data()?;
+        let collection_account = Collection::deserialize(&collection_data[..])?;
+        collection_account.base.current_size as u64
+    };
+    // Assets come in `[asset, oracle]` pairs, like a placement batch
+    let batch_size = product_accounts.len() / 2;
+    require!(
+        product_accounts.chunks_exact(2).remainder().is_empty()
+            && (1..=MAX_STOCKING_BATCH).contains(&batch_size)
+            && product_listing.in_stock + batch_size as u64 <= collection_size,
+        ClickCrateErrors::InvalidStockingRequest
+    );
+
+    let stocking_accounts = StockingAccounts {
+        core_program: ctx.accounts.core_program.to_account_info(),
+        listing_collection: listing_collection.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    };
+
+    for accounts in product_accounts.chunks_exact(2) {
+        let (product_account, oracle_account) = (&accounts[0], &accounts[1]);
+        // Only fresh assets of this listing that are still held by the seller
+        {
+            let product_data = product_account.try_borrow_data()?;
+            let product = Asset::deserialize(&product_data[..])
+                .map_err(|_| ClickCrateErrors::InvalidProductAccount)?;
+            require!(
+                product.base.owner == product_listing.owner
+                    && product.base.update_authority
+                        == UpdateAuthority::Collection(product_listing.id),
+                ClickCrateErrors::InvalidProductAccount
+            );
+            require!(
+                product.external_plugin_adapter_list.oracles.is_empty(),
+                ClickCrateErrors::InvalidStockingRequest
+            );
+        }
+
+        add_product_plugins(
+            product_listing,
+            product_account,
+            oracle_account,
+            &stocking_accounts,
+            ctx.program_id,
+        )?;
+        product_listing.in_stock += 1;
+    }
+
+    emit!(StockAdded {
+        product_listing_id,
+        products: product_accounts
+            .chunks_exact(2)
+            .map(|accounts| accounts[0].key())
+            .collect(),
+        in_stock: product_listing.in_stock,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::ClickCrateClosed;
+use crate::state::{Action, ClickCrateState, Managed};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseClickCrate<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"clickcrate".as_ref(), clickcrate.id.as_ref()],
+        bump,
+        constraint = clickcrate.can(&owner.key(), Action::Close)
+            @ ClickCrateErrors::UnauthorizedClose,
+    )]
+    pub clickcrate: Account<'info, ClickCrateState>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// Deregisters an empty ClickCrate. Listings only leave a ClickCrate once their
+/// orders no longer need it, so no open order can still point at it.
+pub fn close_clickcrate(ctx: Context<CloseClickCrate>) -> Result<()> {
+    let clickcrate = &ctx.accounts.clickcrate;
+    require!(
+        clickcrate.products.is_empty(),
+        ClickCrateErrors::ProductAlreadyPlaced
+    );
+    emit!(ClickCrateClosed {
+        id: clickcrate.id,
+        owner: clickcrate.owner,
+    });
+    msg!("ClickCrate closed");
+    Ok(())
+}
@@ -1,50 +1,104 @@
 use crate::errors::ClickCrateErrors;
-use crate::state::{OrderOracle, ProductListingState};
+use crate::events::OracleClosed;
+use crate::state::{OrderOracle, OrderStatus};
+use crate::Core;
 use anchor_lang::prelude::*;
-use mpl_core::Asset;
+use mpl_core::{
+    instructions::RemoveExternalPluginAdapterV1CpiBuilder,
+    types::{ExternalPluginAdapterKey, UpdateAuthority},
+    Asset, Collection,
+};
 
+/// Works from the listing's collection alone, so oracles can still be closed
+/// after their listing is.
 #[derive(Accounts)]
 #[instruction(product_listing_id: Pubkey, product_id: Pubkey)]
 pub struct CloseOracle<'info> {
-    #[account(
-        mut,
-        seeds = [b"listing".as_ref(), product_listing_id.key().as_ref()],
-        bump,
-    )]
-    pub product_listing: Account<'info, ProductListingState>,
     #[account(
         mut,
         close = owner,
         seeds = [b"oracle", product_id.key().as_ref()],
-        bump,
+        bump = oracle.bump,
     )]
     pub oracle: Account<'info, OrderOracle>,
-    /// CHECK: This is a Metaplex Core NFT
-    #[account(mut)]
+    /// CHECK: This is a Metaplex Core NFT, checked against the listing's collection
+    #[account(mut, address = product_id)]
     pub product: UncheckedAccount<'info>,
+    /// CHECK: This is the Metaplex core collection account, whose address is the listing id
+    #[account(mut, address = product_listing_id @ ClickCrateErrors::InvalidProductAccount)]
+    pub listing_collection: UncheckedAccount<'info>,
+    /// Holds the collection's update authority, which removes the Oracle adapter
     #[account(mut)]
     pub owner: Signer<'info>,
+    pub core_program: Program<'info, Core>,
     pub system_program: Program<'info, System>,
 }
 
+/// Detaches an asset's order oracle and reclaims its rent once the order it
+/// tracks is final, or the asset never sold.
 pub fn close_oracle(
     ctx: Context<CloseOracle>,
-    _product_listing_id: Pubkey,
-    _product_id: Pubkey,
+    product_listing_id: Pubkey,
+    product_id: Pubkey,
 ) -> Result<()> {
-    let product_listing: &mut Account<ProductListingState> = &mut ctx.accounts.product_listing;
-    let product_account = &mut ctx.accounts.product;
-    let product_data = product_account.try_borrow_data()?;
-    require!(
-        Asset::deserialize(&mut &product_data[..]).is_ok(),
-        ClickCrateErrors::InvalidProductAccount
-    );
-    let deserialized_asset = Asset::deserialize(&mut &product_data[..]).unwrap();
+    let oracle_key = ctx.accounts.oracle.key();
     require!(
-        ctx.accounts.owner.key() == product_listing.owner.key()
-            && deserialized_asset.base.owner.key() == product_listing.owner.key(),
-        ClickCrateErrors::UnauthorizedClose
+        matches!(
+            ctx.accounts.oracle.order_status,
+            OrderStatus::Placed | OrderStatus::Completed | OrderStatus::Cancelled
+        ),
+        ClickCrateErrors::OrderNotFinal
     );
 
+    {
+        let collection_data = ctx.accounts.listing_collection.try_borrow_data()?;
+        let collection = Collection::deserialize(&collection_data[..])
+            .map_err(|_| ClickCrateErrors::InvalidProductAccount)?;
+        require!(
+            collection.base.update_authority == ctx.accounts.owner.key(),
+            ClickCrateErrors::UnauthorizedClose
+        );
+    }
+
+    let attached = {
+        let product_data = ctx.accounts.product.try_borrow_data()?;
+        let product = Asset::deserialize(&product_data[..])
+            .map_err(|_| ClickCrateErrors::InvalidProductAccount)?;
+        require!(
+            product.base.update_authority == UpdateAuthority::Collection(product_listing_id),
+            ClickCrateErrors::InvalidProductAccount
+        );
+        // Products the seller still holds are placed until `remove_products` takes
+        // the listing's freeze delegate off them
+        require!(
+            product.base.owner != ctx.accounts.owner.key()
+                || product.plugin_list.freeze_delegate.is_none(),
+            ClickCrateErrors::ProductAlreadyPlaced
+        );
+        product
+            .external_plugin_adapter_list
+            .oracles
+            .iter()
+            .any(|oracle| oracle.base_address == oracle_key)
+    };
+
+    // Removals detach the adapter already, sold products still carry it
+    if attached {
+        RemoveExternalPluginAdapterV1CpiBuilder::new(&ctx.accounts.core_program)
+            .asset(&ctx.accounts.product)
+            .collection(Some(&ctx.accounts.listing_collection))
+            .payer(&ctx.accounts.owner)
+            .authority(Some(&ctx.accounts.owner))
+            .system_program(&ctx.accounts.system_program)
+            .key(ExternalPluginAdapterKey::Oracle(oracle_key))
+            .invoke()?;
+    }
+
+    emit!(OracleClosed {
+        product_listing_id,
+        product_id,
+        oracle: oracle_key,
+    });
+
     Ok(())
 }
@@ -0,0 +1,53 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::ProductListingClosed;
+use crate::state::{Action, Managed, ProductListingState, StockingStatus};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseProductListing<'info> {
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"listing".as_ref(), product_listing.id.as_ref()],
+        bump,
+        constraint = product_listing.can(&owner.key(), Action::Close)
+            @ ClickCrateErrors::UnauthorizedClose,
+    )]
+    pub product_listing: Account<'info, ProductListingState>,
+    /// CHECK: The listing's vault PDA, which `remove_products` closes once swept
+    #[account(
+        seeds = [b"vault".as_ref(), product_listing.id.as_ref()],
+        bump,
+    )]
+    pub vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// Deregisters a listing that is out of every ClickCrate with all its orders
+/// settled. Its oracles can be closed before or after.
+pub fn close_product_listing(ctx: Context<CloseProductListing>) -> Result<()> {
+    let product_listing = &ctx.accounts.product_listing;
+    require!(
+        product_listing.clickcrate_pos.is_none(),
+        ClickCrateErrors::ProductAlreadyPlaced
+    );
+    require!(
+        product_listing.stocking == StockingStatus::Idle,
+        ClickCrateErrors::StockingInProgress
+    );
+    require!(
+        product_listing.open_orders == 0,
+        ClickCrateErrors::OrdersInProgress
+    );
+    require!(
+        product_listing.vault.is_none() && ctx.accounts.vault.data_is_empty(),
+        ClickCrateErrors::VaultNotEmpty
+    );
+    emit!(ProductListingClosed {
+        id: product_listing.id,
+        owner: product_listing.owner,
+    });
+    msg!("Product Listing closed");
+    Ok(())
+}
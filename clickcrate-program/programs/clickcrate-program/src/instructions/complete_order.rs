@@ -1,19 +1,40 @@
 use crate::errors::ClickCrateErrors;
-use crate::state::{OrderOracle, ProductListingState, VaultAccount};
-use crate::OrderStatus;
+use crate::events::OrderCompleted;
+use crate::instructions::place_products::StockingAccounts;
+use crate::payments::VaultPayer;
+use crate::state::{
+    ClickCrateState, Order, OrderOracle, ProductListingState, ProgramConfig, Settlement,
+    VaultAccount,
+};
+use crate::{Core, OrderStatus};
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{program::invoke, system_instruction};
-use mpl_core::Asset;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use mpl_core::{
+    instructions::{TransferV1CpiBuilder, UpdatePluginV1CpiBuilder},
+    types::{FreezeDelegate, Plugin},
+    Asset,
+};
 
 #[derive(Accounts)]
 #[instruction(product_listing_id: Pubkey)]
 pub struct CompleteOrder<'info> {
+    #[account(
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = treasury @ ClickCrateErrors::InvalidTreasury,
+    )]
+    pub config: Account<'info, ProgramConfig>,
     #[account(
     mut,
     seeds = [b"listing".as_ref(), product_listing_id.key().as_ref()],
     bump,
     )]
     pub product_listing: Account<'info, ProductListingState>,
+    #[account(
+        seeds = [b"clickcrate".as_ref(), order.clickcrate_id.as_ref()],
+        bump,
+    )]
+    pub clickcrate: Account<'info, ClickCrateState>,
     #[account(
       mut,
       seeds = [b"vault".as_ref(), product_listing_id.as_ref()],
@@ -26,49 +47,212 @@ pub struct CompleteOrder<'info> {
         bump = oracle.bump,
     )]
     pub oracle: Account<'info, OrderOracle>,
+    #[account(
+        mut,
+        seeds = [b"order".as_ref(), product_listing_id.as_ref(), &order.id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.product_id == product.key() @ ClickCrateErrors::OrderNotFound,
+    )]
+    pub order: Account<'info, Order>,
     /// CHECK: This is the seller's wallet
-    #[account(mut, constraint = seller.key() == product_listing.owner)]
-    pub seller: Signer<'info>,
+    #[account(mut, address = product_listing.owner)]
+    pub seller: UncheckedAccount<'info>,
+    /// CHECK: This is the protocol treasury wallet, checked against the config
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+    /// CHECK: This is the ClickCrate owner's wallet
+    #[account(mut, address = clickcrate.owner)]
+    pub clickcrate_owner: UncheckedAccount<'info>,
+    /// CHECK: This is the buyer's wallet recorded on the order
+    #[account(address = order.buyer)]
+    pub buyer: UncheckedAccount<'info>,
     /// CHECK: This is a Metaplex Core NFT
     #[account(mut)]
     pub product: UncheckedAccount<'info>,
-    #[account(constraint = authority.key() == product_listing.owner)]
-    pub authority: Signer<'info>,
+    /// CHECK: This is the Metaplex core collection account
+    #[account(mut)]
+    pub listing_collection: UncheckedAccount<'info>,
+    /// Anyone can release an order once the buyer confirmed or the dispute window ended
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub vault_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub seller_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub clickcrate_owner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub core_program: Program<'info, Core>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn complete_order(ctx: Context<CompleteOrder>, _product_listing_id: Pubkey) -> Result<()> {
-    let product = Asset::deserialize(&mut &ctx.accounts.product.data.borrow()[..])?;
+pub fn complete_order(ctx: Context<CompleteOrder>, product_listing_id: Pubkey) -> Result<()> {
+    ctx.accounts.config.require_unpaused(
+        &ctx.accounts.product_listing,
+        Some(&ctx.accounts.clickcrate),
+    )?;
+    let product = Asset::deserialize(&ctx.accounts.product.data.borrow()[..])?;
 
     require!(
         product.base.owner.key() == ctx.accounts.seller.key(),
         ClickCrateErrors::UnauthorizedUpdate
     );
 
+    require!(
+        ctx.accounts.order.settlement == Settlement::Unsettled,
+        ClickCrateErrors::OrderAlreadySettled
+    );
+    match ctx.accounts.order.status {
+        OrderStatus::Completed => {}
+        // Delivered orders the buyer never confirmed are released once the window ends
+        OrderStatus::Delivered => {
+            let now = Clock::get()?.unix_timestamp;
+            require!(
+                ctx.accounts
+                    .order
+                    .release_at
+                    .is_some_and(|release_at| now >= release_at),
+                ClickCrateErrors::DisputeWindowOpen
+            );
+            ctx.accounts.order.status = OrderStatus::Completed;
+            ctx.accounts.oracle.set_order_status(OrderStatus::Completed);
+        }
+        OrderStatus::Disputed => return Err(ClickCrateErrors::OrderDisputed.into()),
+        _ => return Err(ClickCrateErrors::OrderNotCompleted.into()),
+    }
     require!(
         ctx.accounts.oracle.order_status == OrderStatus::Completed,
         ClickCrateErrors::OrderNotCompleted
     );
 
-    let amount = ctx.accounts.product_listing.price;
+    let amount = ctx.accounts.order.total_paid;
+    let (fee, remainder) = ctx.accounts.order.split_fee(amount)?;
+    let commission = ctx.accounts.order.commission_for(amount)?;
+    let seller_amount = remainder
+        .checked_sub(commission)
+        .ok_or(ClickCrateErrors::InvalidCommission)?;
 
+    let vault_bump = [ctx.bumps.vault];
+    let vault_seeds: &[&[u8]] = &[b"vault".as_ref(), product_listing_id.as_ref(), &vault_bump];
+    let vault_payer = VaultPayer::new(
+        &ctx.accounts.product_listing,
+        ctx.accounts.vault.to_account_info(),
+        vault_seeds,
+        ctx.accounts.payment_mint.as_ref(),
+        ctx.accounts.vault_token_account.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+    )?;
     require!(
-        **ctx.accounts.vault.to_account_info().lamports.borrow() >= amount.unwrap(),
+        vault_payer.balance()? >= amount,
         ClickCrateErrors::InsufficientBalance
     );
 
-    invoke(
-        &system_instruction::transfer(
-            &ctx.accounts.vault.key(),
-            &ctx.accounts.seller.key(),
-            amount.unwrap(),
-        ),
-        &[
-            ctx.accounts.vault.to_account_info(),
-            ctx.accounts.seller.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-        ],
+    vault_payer.pay(
+        &ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.treasury_token_account.as_ref(),
+        fee,
+    )?;
+    vault_payer.pay(
+        &ctx.accounts.clickcrate_owner.to_account_info(),
+        ctx.accounts.clickcrate_owner_token_account.as_ref(),
+        commission,
+    )?;
+    vault_payer.pay(
+        &ctx.accounts.seller.to_account_info(),
+        ctx.accounts.seller_token_account.as_ref(),
+        seller_amount,
+    )?;
+
+    release_product(
+        &ctx.accounts.product_listing,
+        &ctx.accounts.product.to_account_info(),
+        &ctx.accounts.oracle.to_account_info(),
+        &ctx.accounts.buyer.to_account_info(),
+        &StockingAccounts {
+            core_program: ctx.accounts.core_program.to_account_info(),
+            listing_collection: ctx.accounts.listing_collection.to_account_info(),
+            authority: ctx.accounts.payer.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        ctx.bumps.product_listing,
     )?;
 
+    let order = &mut ctx.accounts.order;
+    order.settlement = Settlement::PaidOut;
+    order.protocol_fee = fee;
+    order.commission = commission;
+    order.seller_proceeds = seller_amount;
+    order.updated_at = Clock::get()?.unix_timestamp;
+
+    let product_listing = &mut ctx.accounts.product_listing;
+    product_listing.fees_paid += fee;
+    product_listing.seller_proceeds += seller_amount;
+    product_listing.commissions_paid += commission;
+    product_listing.record_delivery(order.quantity);
+    product_listing.settle_order();
+    msg!(
+        "Order paid out: {} to seller, {} in commission, {} in fees",
+        seller_amount,
+        commission,
+        fee
+    );
+    emit!(OrderCompleted {
+        order: order.key(),
+        order_id: order.id,
+        product_listing_id,
+        clickcrate_id: order.clickcrate_id,
+        product_id: order.product_id,
+        buyer: order.buyer,
+        seller: ctx.accounts.seller.key(),
+        total_paid: amount,
+        protocol_fee: fee,
+        commission,
+        seller_proceeds: seller_amount,
+        payment_mint: order.payment_mint,
+    });
+
+    Ok(())
+}
+
+/// Unfreezes a sold asset and transfers it to the buyer through the listing's
+/// delegates. The oracle must already approve the transfer.
+pub(crate) fn release_product<'info>(
+    product_listing: &Account<'info, ProductListingState>,
+    product: &AccountInfo<'info>,
+    oracle: &AccountInfo<'info>,
+    buyer: &AccountInfo<'info>,
+    accounts: &StockingAccounts<'info>,
+    listing_bump: u8,
+) -> Result<()> {
+    let listing_seeds: &[&[u8]] = &[
+        b"listing".as_ref(),
+        product_listing.id.as_ref(),
+        &[listing_bump],
+    ];
+    let listing_info = product_listing.to_account_info();
+
+    UpdatePluginV1CpiBuilder::new(&accounts.core_program)
+        .asset(product)
+        .collection(Some(&accounts.listing_collection))
+        .payer(&accounts.authority)
+        .authority(Some(&listing_info))
+        .system_program(&accounts.system_program)
+        .plugin(Plugin::FreezeDelegate(FreezeDelegate { frozen: false }))
+        .invoke_signed(&[listing_seeds])?;
+
+    TransferV1CpiBuilder::new(&accounts.core_program)
+        .asset(product)
+        .collection(Some(&accounts.listing_collection))
+        .payer(&accounts.authority)
+        .authority(Some(&listing_info))
+        .new_owner(buyer)
+        .system_program(Some(&accounts.system_program))
+        .add_remaining_account(oracle, false, false)
+        .invoke_signed(&[listing_seeds])?;
+    msg!("Delivered product to buyer");
+
     Ok(())
 }
@@ -0,0 +1,52 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::OrderStatusChanged;
+use crate::state::{Order, OrderOracle, OrderStatus, Settlement};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(product_listing_id: Pubkey)]
+pub struct ConfirmDelivery<'info> {
+    #[account(
+        mut,
+        seeds = [b"oracle".as_ref(), order.product_id.as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Account<'info, OrderOracle>,
+    #[account(
+        mut,
+        seeds = [b"order".as_ref(), product_listing_id.as_ref(), &order.id.to_le_bytes()],
+        bump = order.bump,
+        has_one = buyer @ ClickCrateErrors::UnauthorizedUpdate,
+    )]
+    pub order: Account<'info, Order>,
+    pub buyer: Signer<'info>,
+}
+
+pub fn confirm_delivery(ctx: Context<ConfirmDelivery>, product_listing_id: Pubkey) -> Result<()> {
+    let oracle = &mut ctx.accounts.oracle;
+    let order = &mut ctx.accounts.order;
+    require!(
+        order.settlement == Settlement::Unsettled,
+        ClickCrateErrors::OrderAlreadySettled
+    );
+    require!(
+        order.status == OrderStatus::Delivered && oracle.order_status == OrderStatus::Delivered,
+        ClickCrateErrors::InvalidStatusTransition
+    );
+
+    // Confirming receipt waives the rest of the dispute window
+    order.status = OrderStatus::Completed;
+    order.updated_at = Clock::get()?.unix_timestamp;
+    oracle.set_order_status(OrderStatus::Completed);
+    emit!(OrderStatusChanged {
+        order: order.key(),
+        order_id: order.id,
+        product_listing_id,
+        product_id: order.product_id,
+        authority: ctx.accounts.buyer.key(),
+        previous_status: OrderStatus::Delivered,
+        new_status: OrderStatus::Completed,
+        release_at: order.release_at,
+    });
+    Ok(())
+}
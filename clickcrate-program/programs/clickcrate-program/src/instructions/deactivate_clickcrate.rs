@@ -1,4 +1,6 @@
-use crate::state::ClickCrateState;
+use crate::errors::ClickCrateErrors;
+use crate::events::ClickCrateDeactivated;
+use crate::state::{Action, ClickCrateState, Managed};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -7,14 +9,16 @@ pub struct DeactivateClickCrate<'info> {
         mut,
         seeds = [b"clickcrate".as_ref(), clickcrate.id.as_ref()],
         bump,
-        has_one = owner
+        constraint = clickcrate.can(&authority.key(), Action::SetActive)
+            @ ClickCrateErrors::UnauthorizedUpdate,
     )]
     pub clickcrate: Account<'info, ClickCrateState>,
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
 }
 
 pub fn deactivate_clickcrate(ctx: Context<DeactivateClickCrate>) -> Result<()> {
     let clickcrate = &mut ctx.accounts.clickcrate;
     clickcrate.is_active = false;
+    emit!(ClickCrateDeactivated { id: clickcrate.id });
     Ok(())
 }
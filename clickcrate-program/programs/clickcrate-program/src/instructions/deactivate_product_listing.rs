@@ -1,4 +1,6 @@
-use crate::state::ProductListingState;
+use crate::errors::ClickCrateErrors;
+use crate::events::ProductListingDeactivated;
+use crate::state::{Action, Managed, ProductListingState};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -7,14 +9,18 @@ pub struct DeactivateProductListing<'info> {
         mut,
         seeds = [b"listing".as_ref(), product_listing.id.as_ref()],
         bump,
-        has_one = owner
+        constraint = product_listing.can(&authority.key(), Action::SetActive)
+            @ ClickCrateErrors::UnauthorizedUpdate,
     )]
     pub product_listing: Account<'info, ProductListingState>,
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
 }
 
 pub fn deactivate_product_listing(ctx: Context<DeactivateProductListing>) -> Result<()> {
     let product_listing = &mut ctx.accounts.product_listing;
     product_listing.is_active = false;
+    emit!(ProductListingDeactivated {
+        id: product_listing.id
+    });
     Ok(())
 }
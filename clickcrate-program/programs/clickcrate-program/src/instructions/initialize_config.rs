@@ -0,0 +1,65 @@
+use crate::constants::{
+    DEFAULT_DISPUTE_WINDOW_SECONDS, DEFAULT_FEE_BASIS_POINTS, MAX_CLICKCRATE_PRODUCTS,
+};
+use crate::errors::ClickCrateErrors;
+use crate::events::ConfigInitialized;
+use crate::program::ClickcrateProgram;
+use crate::state::{Origin, ProgramConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        seeds = [b"config".as_ref()],
+        bump,
+        payer = admin,
+        space = 8 + ProgramConfig::MAX_SIZE,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, ClickcrateProgram>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ ClickCrateErrors::UnauthorizedAdmin
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_config(ctx: Context<InitializeConfig>, treasury: Pubkey) -> Result<()> {
+    require!(
+        treasury != Pubkey::default(),
+        ClickCrateErrors::InvalidTreasury
+    );
+
+    // Third-party integrations are opted into through `update_config`
+    let allowed_origins = vec![Origin::Clickcrate];
+    let max_clickcrate_products = MAX_CLICKCRATE_PRODUCTS as u8;
+    ctx.accounts.config.set_inner(ProgramConfig {
+        version: ProgramConfig::VERSION,
+        admin: ctx.accounts.admin.key(),
+        pending_admin: None,
+        treasury,
+        arbiter: ctx.accounts.admin.key(),
+        fee_basis_points: DEFAULT_FEE_BASIS_POINTS,
+        dispute_window_seconds: DEFAULT_DISPUTE_WINDOW_SECONDS,
+        allowed_origins: allowed_origins.clone(),
+        max_clickcrate_products,
+        paused: false,
+        bump: ctx.bumps.config,
+        reserved: [0; 64],
+    });
+    emit!(ConfigInitialized {
+        admin: ctx.accounts.admin.key(),
+        treasury,
+        arbiter: ctx.accounts.admin.key(),
+        fee_basis_points: DEFAULT_FEE_BASIS_POINTS,
+        dispute_window_seconds: DEFAULT_DISPUTE_WINDOW_SECONDS,
+        allowed_origins,
+        max_clickcrate_products,
+    });
+    Ok(())
+}
@@ -1,6 +1,5 @@
-use crate::state::{
-    ExternalValidationResult, OracleValidation, OrderOracle, OrderStatus, ProductListingState,
-};
+use crate::events::OracleInitialized;
+use crate::state::{OrderOracle, ProductListingState};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -33,22 +32,20 @@ pub struct InitializeOracle<'info> {
 
 pub fn initialize_oracle(
     ctx: Context<InitializeOracle>,
-    _product_listing_id: Pubkey,
-    _product_id: Pubkey,
+    product_listing_id: Pubkey,
+    product_id: Pubkey,
 ) -> Result<()> {
     let oracle = &mut ctx.accounts.oracle;
     let product_listing = &ctx.accounts.product_listing;
 
-    oracle.set_inner(OrderOracle {
-        order_status: OrderStatus::Placed,
-        order_manager: product_listing.order_manager.clone(),
-        validation: OracleValidation::V1 {
-            create: ExternalValidationResult::Pass,
-            transfer: ExternalValidationResult::Rejected,
-            burn: ExternalValidationResult::Pass,
-            update: ExternalValidationResult::Pass,
-        },
-        bump: ctx.bumps.oracle,
+    oracle.set_inner(OrderOracle::new(
+        product_listing.order_manager.clone(),
+        ctx.bumps.oracle,
+    ));
+    emit!(OracleInitialized {
+        product_listing_id,
+        product_id,
+        oracle: oracle.key(),
     });
 
     Ok(())
@@ -1,11 +1,18 @@
+use crate::constants::MAX_BASIS_POINTS;
 use crate::errors::ClickCrateErrors;
+use crate::events::PurchaseMade;
+use crate::payments::{
+    gross_amount_for, require_payment_mint, require_token_account, require_vault_token_account,
+    transfer_tokens,
+};
 use crate::state::{
-    ClickCrateState, ExternalValidationResult, OracleValidation, OrderOracle, OrderStatus,
-    ProductListingState, VaultAccount,
+    ClickCrateState, ExternalValidationResult, OracleValidation, Order, OrderOracle, OrderStatus,
+    ProductListingState, ProgramConfig, Settlement, StockingStatus, VaultAccount,
 };
 use crate::utils::Core;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::{program::invoke, system_instruction};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use mpl_core::{
     instructions::UpdatePluginV1CpiBuilder,
     types::{Attribute, Attributes, Plugin},
@@ -14,9 +21,10 @@ use mpl_core::{
 #[derive(Accounts)]
 #[instruction(product_listing_id: Pubkey, clickcrate_id: Pubkey, product_id: Pubkey, quantity: u64)]
 pub struct MakePurchase<'info> {
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
     #[account(
       mut,
-      has_one = owner,
       seeds = [b"clickcrate".as_ref(), clickcrate_id.key().as_ref()],
       bump,
     )]
@@ -40,24 +48,42 @@ pub struct MakePurchase<'info> {
       bump,
     )]
     pub vault: Account<'info, VaultAccount>,
-    /// CHECK: This is the Metaplex core collection account
-    #[account(mut)]
+    #[account(
+      init,
+      seeds = [
+        b"order".as_ref(),
+        product_listing_id.as_ref(),
+        &product_listing.order_count.to_le_bytes(),
+      ],
+      bump,
+      payer = buyer,
+      space = 8 + Order::MAX_SIZE,
+    )]
+    pub order: Account<'info, Order>,
+    /// CHECK: This is the Metaplex core collection account, whose address is the listing id
+    #[account(mut, address = product_listing.id)]
     pub listing_collection: UncheckedAccount<'info>,
     /// CHECK: This is a Metaplex Core NFT
     #[account(mut)]
     pub product_account: UncheckedAccount<'info>,
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub buyer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub vault_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut, constraint = owner.key() == product_listing.owner)]
     pub owner: Signer<'info>,
     #[account(mut)]
     pub buyer: Signer<'info>,
     pub core_program: Program<'info, Core>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn make_purchase(
     ctx: Context<MakePurchase>,
     _product_listing_id: Pubkey,
-    _clickcrate_id: Pubkey,
+    clickcrate_id: Pubkey,
     product_id: Pubkey,
     quantity: u64,
 ) -> Result<()> {
@@ -66,8 +92,12 @@ pub fn make_purchase(
     let oracle = &mut ctx.accounts.oracle;
     let product = &ctx.accounts.product_account;
 
+    ctx.accounts
+        .config
+        .require_unpaused(product_listing, Some(clickcrate))?;
+
     require!(
-        clickcrate.product == Some(product_listing.id),
+        clickcrate.products.contains(&product_listing.id),
         ClickCrateErrors::ProductNotFound
     );
 
@@ -81,6 +111,14 @@ pub fn make_purchase(
         ClickCrateErrors::ProductNotPlaced
     );
 
+    require!(
+        product_listing.stocking == StockingStatus::Idle,
+        ClickCrateErrors::StockingInProgress
+    );
+
+    // Each asset carries its own oracle, so an order can only ever deliver one of them
+    require!(quantity == 1, ClickCrateErrors::InvalidQuantity);
+
     require!(
         product_listing.in_stock >= quantity,
         ClickCrateErrors::ProductOutOfStock
@@ -91,10 +129,69 @@ pub fn make_purchase(
         ClickCrateErrors::PriceNotFound
     );
 
-    let amount = product_listing.price.unwrap() * quantity;
-    let user_lamports = ctx.accounts.buyer.lamports();
+    // Payouts use the rates in force now, however the config or ClickCrate change later
+    let fee_basis_points = ctx.accounts.config.fee_basis_points;
+    let commission_basis_points = clickcrate.commission_basis_points;
+    require!(
+        fee_basis_points
+            .checked_add(commission_basis_points)
+            .is_some_and(|total| total <= MAX_BASIS_POINTS),
+        ClickCrateErrors::InvalidCommission
+    );
+
+    let unit_price = product_listing.price.unwrap();
+    let amount = unit_price
+        .checked_mul(quantity)
+        .ok_or(ClickCrateErrors::AmountOverflow)?;
 
-    if user_lamports >= amount {
+    if let Some(payment_mint) = product_listing.payment_mint {
+        require_payment_mint(
+            &payment_mint,
+            ctx.accounts.payment_mint.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+        )?;
+        let (Some(mint), Some(buyer_token_account), Some(vault_token_account), Some(token_program)) = (
+            &ctx.accounts.payment_mint,
+            &ctx.accounts.buyer_token_account,
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.token_program,
+        ) else {
+            return Err(ClickCrateErrors::MissingPaymentAccounts.into());
+        };
+        require_token_account(buyer_token_account, &payment_mint, ctx.accounts.buyer.key)?;
+        require_vault_token_account(
+            &vault_token_account.to_account_info(),
+            &ctx.accounts.vault.key(),
+            &payment_mint,
+            &token_program.key(),
+        )?;
+
+        // The buyer covers any transfer fee so the vault receives the full amount
+        let gross_amount = gross_amount_for(mint, amount)?;
+        require!(
+            buyer_token_account.amount >= gross_amount,
+            ClickCrateErrors::InsufficientBalance
+        );
+        transfer_tokens(
+            token_program,
+            mint,
+            buyer_token_account.to_account_info(),
+            vault_token_account.to_account_info(),
+            ctx.accounts.buyer.to_account_info(),
+            gross_amount,
+            &[],
+        )?;
+    } else {
+        let buyer_rent_exempt_minimum =
+            Rent::get()?.minimum_balance(ctx.accounts.buyer.to_account_info().data_len());
+        require!(
+            ctx.accounts
+                .buyer
+                .lamports()
+                .saturating_sub(buyer_rent_exempt_minimum)
+                >= amount,
+            ClickCrateErrors::InsufficientBalance
+        );
         invoke(
             &system_instruction::transfer(
                 ctx.accounts.buyer.key,
@@ -114,6 +211,47 @@ pub fn make_purchase(
     product_listing.sold += quantity;
     msg!("Updated listing");
 
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.order.set_inner(Order {
+        version: Order::VERSION,
+        id: product_listing.order_count,
+        buyer: ctx.accounts.buyer.key(),
+        product_listing_id: product_listing.id,
+        clickcrate_id,
+        product_id,
+        quantity,
+        unit_price,
+        total_paid: amount,
+        payment_mint: product_listing.payment_mint,
+        status: OrderStatus::Pending,
+        settlement: Settlement::Unsettled,
+        fee_basis_points,
+        commission_basis_points,
+        protocol_fee: 0,
+        commission: 0,
+        seller_proceeds: 0,
+        created_at: now,
+        updated_at: now,
+        release_at: None,
+        bump: ctx.bumps.order,
+        reserved: [0; 64],
+    });
+    product_listing.order_count += 1;
+    product_listing.open_orders += 1;
+    msg!("Recorded order {}", ctx.accounts.order.id);
+    emit!(PurchaseMade {
+        order: ctx.accounts.order.key(),
+        order_id: ctx.accounts.order.id,
+        product_listing_id: product_listing.id,
+        clickcrate_id,
+        product_id,
+        buyer: ctx.accounts.buyer.key(),
+        quantity,
+        unit_price,
+        total_paid: amount,
+        payment_mint: product_listing.payment_mint,
+    });
+
     require!(
         oracle.validation
             == OracleValidation::V1 {
@@ -0,0 +1,27 @@
+use crate::events::AccountMigrated;
+use crate::migrations::migrate_account;
+use crate::state::ClickCrateState;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MigrateClickCrate<'info> {
+    /// CHECK: Read in whichever layout it was written with by `migrate_account`
+    #[account(mut, owner = crate::ID)]
+    pub clickcrate: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_clickcrate(ctx: Context<MigrateClickCrate>) -> Result<()> {
+    migrate_account::<ClickCrateState>(
+        &ctx.accounts.clickcrate,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+    emit!(AccountMigrated {
+        account: ctx.accounts.clickcrate.key(),
+        version: ClickCrateState::VERSION,
+    });
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::events::AccountMigrated;
+use crate::migrations::migrate_account;
+use crate::state::OrderOracle;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MigrateOracle<'info> {
+    /// CHECK: Read in whichever layout it was written with by `migrate_account`
+    #[account(mut, owner = crate::ID)]
+    pub oracle: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_oracle(ctx: Context<MigrateOracle>) -> Result<()> {
+    migrate_account::<OrderOracle>(
+        &ctx.accounts.oracle,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+    emit!(AccountMigrated {
+        account: ctx.accounts.oracle.key(),
+        version: OrderOracle::VERSION,
+    });
+    Ok(())
+}
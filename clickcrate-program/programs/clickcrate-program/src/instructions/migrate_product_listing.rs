@@ -0,0 +1,27 @@
+use crate::events::AccountMigrated;
+use crate::migrations::migrate_account;
+use crate::state::ProductListingState;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MigrateProductListing<'info> {
+    /// CHECK: Read in whichever layout it was written with by `migrate_account`
+    #[account(mut, owner = crate::ID)]
+    pub product_listing: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_product_listing(ctx: Context<MigrateProductListing>) -> Result<()> {
+    migrate_account::<ProductListingState>(
+        &ctx.accounts.product_listing,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+    emit!(AccountMigrated {
+        account: ctx.accounts.product_listing.key(),
+        version: ProductListingState::VERSION,
+    });
+    Ok(())
+}
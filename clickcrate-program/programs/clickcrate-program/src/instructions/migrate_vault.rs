@@ -0,0 +1,27 @@
+use crate::events::AccountMigrated;
+use crate::migrations::migrate_account;
+use crate::state::VaultAccount;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    /// CHECK: Read in whichever layout it was written with by `migrate_account`
+    #[account(mut, owner = crate::ID)]
+    pub vault: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+    migrate_account::<VaultAccount>(
+        &ctx.accounts.vault,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+    emit!(AccountMigrated {
+        account: ctx.accounts.vault.key(),
+        version: VaultAccount::VERSION,
+    });
+    Ok(())
+}
@@ -1,31 +1,75 @@
+pub mod abort_stocking;
+pub mod accept_admin;
 pub mod activate_clickcrate;
 pub mod activate_product_listing;
+pub mod add_stock;
+pub mod close_clickcrate;
 pub mod close_oracle;
+pub mod close_product_listing;
 pub mod complete_order;
+pub mod confirm_delivery;
 pub mod deactivate_clickcrate;
 pub mod deactivate_product_listing;
+pub mod initialize_config;
 pub mod initialize_oracle;
 pub mod make_purchase;
+pub mod migrate_clickcrate;
+pub mod migrate_oracle;
+pub mod migrate_product_listing;
+pub mod migrate_vault;
+pub mod open_dispute;
 pub mod place_products;
+pub mod propose_admin;
+pub mod refund_order;
 pub mod register_clickcrate;
 pub mod register_product_listing;
 pub mod remove_products;
+pub mod resolve_dispute;
+pub mod resume_placement;
+pub mod set_clickcrate_paused;
+pub mod set_paused;
+pub mod set_product_listing_paused;
+pub mod transfer_clickcrate_ownership;
+pub mod transfer_product_listing_ownership;
 pub mod update_clickcrate;
+pub mod update_config;
 pub mod update_order_status;
 pub mod update_product_listing;
 
+pub use abort_stocking::*;
+pub use accept_admin::*;
 pub use activate_clickcrate::*;
 pub use activate_product_listing::*;
+pub use add_stock::*;
+pub use close_clickcrate::*;
 pub use close_oracle::*;
+pub use close_product_listing::*;
 pub use complete_order::*;
+pub use confirm_delivery::*;
 pub use deactivate_clickcrate::*;
 pub use deactivate_product_listing::*;
+pub use initialize_config::*;
 pub use initialize_oracle::*;
 pub use make_purchase::*;
+pub use migrate_clickcrate::*;
+pub use migrate_oracle::*;
+pub use migrate_product_listing::*;
+pub use migrate_vault::*;
+pub use open_dispute::*;
 pub use place_products::*;
+pub use propose_admin::*;
+pub use refund_order::*;
 pub use register_clickcrate::*;
 pub use register_product_listing::*;
 pub use remove_products::*;
+pub use resolve_dispute::*;
+pub use resume_placement::*;
+pub use set_clickcrate_paused::*;
+pub use set_paused::*;
+pub use set_product_listing_paused::*;
+pub use transfer_clickcrate_ownership::*;
+pub use transfer_product_listing_ownership::*;
 pub use update_clickcrate::*;
+pub use update_config::*;
 pub use update_order_status::*;
 pub use update_product_listing::*;
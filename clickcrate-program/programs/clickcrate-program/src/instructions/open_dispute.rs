@@ -0,0 +1,48 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::DisputeOpened;
+use crate::state::{Order, OrderOracle, OrderStatus};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(product_listing_id: Pubkey)]
+pub struct OpenDispute<'info> {
+    #[account(
+        mut,
+        seeds = [b"oracle".as_ref(), order.product_id.as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Account<'info, OrderOracle>,
+    #[account(
+        mut,
+        seeds = [b"order".as_ref(), product_listing_id.as_ref(), &order.id.to_le_bytes()],
+        bump = order.bump,
+        has_one = buyer @ ClickCrateErrors::UnauthorizedUpdate,
+    )]
+    pub order: Account<'info, Order>,
+    pub buyer: Signer<'info>,
+}
+
+/// Freezes an order's escrow until the config's arbiter resolves the dispute.
+pub fn open_dispute(ctx: Context<OpenDispute>, product_listing_id: Pubkey) -> Result<()> {
+    let oracle = &mut ctx.accounts.oracle;
+    let order = &mut ctx.accounts.order;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        order.can_open_dispute(now) && oracle.order_status == order.status,
+        ClickCrateErrors::DisputeNotAllowed
+    );
+
+    let previous_status = std::mem::replace(&mut order.status, OrderStatus::Disputed);
+    order.updated_at = now;
+    oracle.set_order_status(OrderStatus::Disputed);
+    msg!("Dispute opened for order {}", order.id);
+    emit!(DisputeOpened {
+        order: order.key(),
+        order_id: order.id,
+        product_listing_id,
+        product_id: order.product_id,
+        buyer: order.buyer,
+        previous_status,
+    });
+    Ok(())
+}
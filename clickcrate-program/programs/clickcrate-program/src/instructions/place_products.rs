@@ -1,47 +1,78 @@
+use crate::constants::MAX_STOCKING_BATCH;
 use crate::errors::ClickCrateErrors;
-use crate::state::{ClickCrateState, ProductListingState, VaultAccount};
+use crate::events::ProductsPlaced;
+use crate::payments::{require_payment_mint, require_vault_token_account};
+use crate::state::{
+    Action, ClickCrateState, Managed, OrderOracle, OrderStatus, ProductListingState, ProgramConfig,
+    StockingStatus, VaultAccount,
+};
 use crate::Core;
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{
+    allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+};
+use anchor_spl::{
+    associated_token::{self, AssociatedToken},
+    token_interface::{Mint, TokenInterface},
+};
 use mpl_core::{
     instructions::{AddExternalPluginAdapterV1CpiBuilder, AddPluginV1CpiBuilder},
     types::{
         ExternalCheckResult, ExternalPluginAdapterInitInfo, FreezeDelegate, HookableLifecycleEvent,
-        OracleInitInfo, Plugin, PluginAuthority, TransferDelegate, ValidationResultsOffset,
+        OracleInitInfo, Plugin, PluginAuthority, TransferDelegate, UpdateAuthority,
+        ValidationResultsOffset,
     },
-    Collection,
+    Asset, Collection,
 };
 
 #[derive(Accounts)]
 #[instruction(product_listing_id: Pubkey, clickcrate_id: Pubkey, price: u64)]
 pub struct PlaceProducts<'info> {
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
     #[account(
         mut,
-        has_one = owner,
         seeds = [b"clickcrate".as_ref(), clickcrate_id.key().as_ref()],
         bump,
+        realloc = 8 + ClickCrateState::size_for(clickcrate.products.len() + 1),
+        realloc::payer = authority,
+        realloc::zero = false,
     )]
     pub clickcrate: Account<'info, ClickCrateState>,
     #[account(
         mut,
-        has_one = owner,
         seeds = [b"listing".as_ref(), product_listing_id.key().as_ref()],
         bump,
+        constraint = product_listing.can(&authority.key(), Action::PlaceProducts)
+            @ ClickCrateErrors::UnauthorizedUpdate,
     )]
     pub product_listing: Account<'info, ProductListingState>,
     #[account(
       init,
       seeds = [b"vault".as_ref(), product_listing_id.key().as_ref()],
       bump,
-      payer = owner,
+      payer = authority,
       space = 8 + VaultAccount::MAX_SIZE,
   )]
     pub vault: Account<'info, VaultAccount>,
-    /// CHECK: This is the Metaplex core collection account
-    #[account(mut)]
+    /// CHECK: This is the Metaplex core collection account, whose address is the listing id
+    #[account(mut, address = product_listing.id)]
     pub listing_collection: UncheckedAccount<'info>,
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    /// CHECK: Associated token account of the vault, created here when the listing is paid in tokens
+    #[account(mut)]
+    pub vault_token_account: Option<UncheckedAccount<'info>>,
+    /// Signs the Core plugin CPIs, so it must also hold authority over the assets
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
+    #[account(
+        constraint = clickcrate.can(&clickcrate_authority.key(), Action::HostProducts)
+            @ ClickCrateErrors::UnauthorizedUpdate,
+    )]
+    pub clickcrate_authority: Signer<'info>,
     pub core_program: Program<'info, Core>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
     pub system_program: Program<'info, System>,
 }
 
@@ -53,19 +84,18 @@ pub fn place_products<'a, 'b, 'c: 'info, 'info>(
 ) -> Result<()> {
     let clickcrate: &mut Account<ClickCrateState> = &mut ctx.accounts.clickcrate;
     let product_listing: &mut Account<ProductListingState> = &mut ctx.accounts.product_listing;
-    let vault = &ctx.accounts.vault;
+    let vault = &mut ctx.accounts.vault;
     let listing_collection = &ctx.accounts.listing_collection;
     let product_accounts = ctx.remaining_accounts;
 
-    let total_minted = {
+    // Burned assets no longer count towards the collection's size
+    let collection_size = {
         let collection_data = listing_collection.try_borrow_data()?;
-        let collection_account = Collection::deserialize(&mut &collection_data[..])?;
-        collection_account.base.num_minted
+        let collection_account = Collection::deserialize(&collection_data[..])?;
+        collection_account.base.current_size
     };
 
-    let core_program_info = ctx.accounts.core_program.to_account_info();
-    let collection_info = ctx.accounts.listing_collection.to_account_info();
-    let owner_info = ctx.accounts.owner.to_account_info();
+    let authority_info = ctx.accounts.authority.to_account_info();
     let system_program_info = ctx.accounts.system_program.to_account_info();
 
     require!(
@@ -76,69 +106,308 @@ pub fn place_products<'a, 'b, 'c: 'info, 'info>(
         clickcrate.is_active,
         ClickCrateErrors::ClickCrateDeactivated
     );
+    ctx.accounts
+        .config
+        .require_unpaused(product_listing, Some(clickcrate))?;
+    clickcrate.require_eligible(
+        &product_listing.placement_type,
+        &product_listing.product_category,
+    )?;
+    require!(
+        !clickcrate.products.contains(&product_listing.id),
+        ClickCrateErrors::ProductAlreadyPlaced
+    );
+    require!(
+        clickcrate.products.len() < ctx.accounts.config.max_clickcrate_products as usize,
+        ClickCrateErrors::ClickCrateFull
+    );
+    require!(
+        product_listing.stocking == StockingStatus::Idle,
+        ClickCrateErrors::StockingInProgress
+    );
     require!(
-        product_listing.in_stock == 0
-            && product_listing.sold == 0
-            && product_accounts.len() as u32 == total_minted
-            && product_accounts.len() >= 1
-            && product_accounts.len() <= 20,
+        product_listing.in_stock == 0 && product_listing.sold == 0 && collection_size > 0,
         ClickCrateErrors::InvalidStockingRequest
     );
 
-    for product_account in product_accounts.iter() {
-        AddPluginV1CpiBuilder::new(&core_program_info)
-            .asset(product_account)
-            .collection(Some(&collection_info))
-            .payer(&owner_info)
-            .authority(Some(&owner_info))
-            .system_program(&system_program_info)
-            .plugin(Plugin::FreezeDelegate(FreezeDelegate { frozen: true }))
-            .init_authority(PluginAuthority::Address {
-                address: product_listing.key(),
-            })
-            .invoke()?;
-
-        AddPluginV1CpiBuilder::new(&core_program_info)
-            .asset(&product_account)
-            .collection(Some(&collection_info))
-            .payer(&owner_info)
-            .authority(Some(&owner_info))
-            .system_program(&system_program_info)
-            .plugin(Plugin::TransferDelegate(TransferDelegate {}))
-            .init_authority(PluginAuthority::Address {
-                address: product_listing.key(),
-            })
-            .invoke()?;
-
-        let (oracle_pda, _) = Pubkey::find_program_address(
-            &[b"oracle", product_account.key().as_ref()],
-            ctx.program_id,
-        );
-        AddExternalPluginAdapterV1CpiBuilder::new(&core_program_info)
-            .asset(&product_account)
-            .collection(Some(&collection_info))
-            .payer(&owner_info)
-            .authority(Some(&owner_info))
-            .system_program(&system_program_info)
-            .init_info(ExternalPluginAdapterInitInfo::Oracle(OracleInitInfo {
-                base_address: oracle_pda,
-                results_offset: Some(ValidationResultsOffset::Anchor),
-                lifecycle_checks: vec![(
-                    HookableLifecycleEvent::Transfer,
-                    ExternalCheckResult { flags: 4 },
-                )],
-                base_address_config: None,
-                init_plugin_authority: None,
-            }))
-            .invoke()?;
-        product_listing.in_stock += 1;
-        msg!("Processed product account: {}", product_account.key());
+    if let Some(payment_mint) = product_listing.payment_mint {
+        require_payment_mint(
+            &payment_mint,
+            ctx.accounts.payment_mint.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+        )?;
+        let (
+            Some(mint),
+            Some(vault_token_account),
+            Some(token_program),
+            Some(associated_token_program),
+        ) = (
+            &ctx.accounts.payment_mint,
+            &ctx.accounts.vault_token_account,
+            &ctx.accounts.token_program,
+            &ctx.accounts.associated_token_program,
+        )
+        else {
+            return Err(ClickCrateErrors::MissingPaymentAccounts.into());
+        };
+        require_vault_token_account(
+            vault_token_account,
+            &vault.key(),
+            &payment_mint,
+            &token_program.key(),
+        )?;
+        associated_token::create_idempotent(CpiContext::new(
+            associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: authority_info.clone(),
+                associated_token: vault_token_account.to_account_info(),
+                authority: vault.to_account_info(),
+                mint: mint.to_account_info(),
+                system_program: system_program_info.clone(),
+                token_program: token_program.to_account_info(),
+            },
+        ))?;
     }
 
+    // Large collections are placed over several transactions, see resume_placement
+    // and abort_stocking
+    product_listing.begin_stocking(StockingStatus::Placing, collection_size);
+    let stocking_accounts = StockingAccounts {
+        core_program: ctx.accounts.core_program.to_account_info(),
+        listing_collection: ctx.accounts.listing_collection.to_account_info(),
+        authority: authority_info.clone(),
+        system_program: system_program_info.clone(),
+    };
+    place_batch(
+        product_listing,
+        product_accounts,
+        &stocking_accounts,
+        ctx.program_id,
+    )?;
+
     product_listing.clickcrate_pos = Some(clickcrate.id);
+    vault.version = VaultAccount::VERSION;
+    vault.bump = ctx.bumps.vault;
     product_listing.vault = Some(vault.key());
     product_listing.price = Some(price);
-    clickcrate.product = Some(product_listing.id);
+    product_listing.fee_basis_points = ctx.accounts.config.fee_basis_points;
+    clickcrate.products.push(product_listing.id);
+    emit!(ProductsPlaced {
+        product_listing_id: product_listing.id,
+        clickcrate_id: clickcrate.id,
+        vault: vault.key(),
+        products: product_accounts
+            .chunks_exact(2)
+            .map(|accounts| accounts[0].key())
+            .collect(),
+        price,
+        payment_mint: product_listing.payment_mint,
+        in_stock: product_listing.in_stock,
+        remaining: product_listing.stocking_remaining(),
+    });
 
     Ok(())
 }
+
+/// Stocks the next batch of a placement in progress, passed as `[asset, oracle]`
+/// pairs of assets in the listing's collection that the seller still holds.
+pub(crate) fn place_batch<'info>(
+    product_listing: &mut Account<'info, ProductListingState>,
+    product_accounts: &[AccountInfo<'info>],
+    stocking_accounts: &StockingAccounts<'info>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    require!(
+        product_accounts.chunks_exact(2).remainder().is_empty()
+            && (1..=MAX_STOCKING_BATCH).contains(&(product_accounts.len() / 2)),
+        ClickCrateErrors::InvalidStockingRequest
+    );
+    // Core rejects adding the same plugins twice, so an asset can't be counted twice
+    for accounts in product_accounts.chunks_exact(2) {
+        let (product_account, oracle_account) = (&accounts[0], &accounts[1]);
+        {
+            let product_data = product_account.try_borrow_data()?;
+            let product = Asset::deserialize(&product_data[..])
+                .map_err(|_| ClickCrateErrors::InvalidProductAccount)?;
+            require!(
+                product.base.owner == product_listing.owner
+                    && product.base.update_authority
+                        == UpdateAuthority::Collection(product_listing.id),
+                ClickCrateErrors::InvalidProductAccount
+            );
+        }
+        add_product_plugins(
+            product_listing,
+            product_account,
+            oracle_account,
+            stocking_accounts,
+            program_id,
+        )?;
+        product_listing.in_stock += 1;
+        msg!("Processed product account: {}", product_account.key());
+    }
+    product_listing.advance_stocking(product_accounts.len() / 2)
+}
+
+/// Accounts the Core CPIs need to stock or release a listing's assets.
+pub(crate) struct StockingAccounts<'info> {
+    pub core_program: AccountInfo<'info>,
+    pub listing_collection: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+/// Hands freeze and transfer authority over an asset to the listing PDA and
+/// attaches the asset's order oracle, creating the oracle if needed.
+pub(crate) fn add_product_plugins<'info>(
+    product_listing: &Account<'info, ProductListingState>,
+    product_account: &AccountInfo<'info>,
+    oracle_account: &AccountInfo<'info>,
+    stocking_accounts: &StockingAccounts<'info>,
+    program_id: &Pubkey,
+) -> Result<()> {
+    let StockingAccounts {
+        core_program,
+        listing_collection,
+        authority,
+        system_program,
+    } = stocking_accounts;
+    let oracle_pda = ensure_order_oracle(
+        product_listing,
+        product_account,
+        oracle_account,
+        authority,
+        system_program,
+        program_id,
+    )?;
+
+    AddPluginV1CpiBuilder::new(core_program)
+        .asset(product_account)
+        .collection(Some(listing_collection))
+        .payer(authority)
+        .authority(Some(authority))
+        .system_program(system_program)
+        .plugin(Plugin::FreezeDelegate(FreezeDelegate { frozen: true }))
+        .init_authority(PluginAuthority::Address {
+            address: product_listing.key(),
+        })
+        .invoke()?;
+
+    AddPluginV1CpiBuilder::new(core_program)
+        .asset(product_account)
+        .collection(Some(listing_collection))
+        .payer(authority)
+        .authority(Some(authority))
+        .system_program(system_program)
+        .plugin(Plugin::TransferDelegate(TransferDelegate {}))
+        .init_authority(PluginAuthority::Address {
+            address: product_listing.key(),
+        })
+        .invoke()?;
+
+    AddExternalPluginAdapterV1CpiBuilder::new(core_program)
+        .asset(product_account)
+        .collection(Some(listing_collection))
+        .payer(authority)
+        .authority(Some(authority))
+        .system_program(system_program)
+        .init_info(ExternalPluginAdapterInitInfo::Oracle(OracleInitInfo {
+            base_address: oracle_pda,
+            results_offset: Some(ValidationResultsOffset::Anchor),
+            lifecycle_checks: vec![(
+                HookableLifecycleEvent::Transfer,
+                ExternalCheckResult { flags: 4 },
+            )],
+            base_address_config: None,
+            init_plugin_authority: None,
+        }))
+        .invoke()?;
+
+    Ok(())
+}
+
+/// Creates the asset's order oracle, or checks the one already initialized for
+/// it, and returns its address.
+fn ensure_order_oracle<'info>(
+    product_listing: &ProductListingState,
+    product_account: &AccountInfo<'info>,
+    oracle_account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<Pubkey> {
+    let (oracle_pda, bump) =
+        Pubkey::find_program_address(&[b"oracle", product_account.key().as_ref()], program_id);
+    require!(
+        oracle_account.key() == oracle_pda,
+        ClickCrateErrors::OracleNotFound
+    );
+
+    if oracle_account.owner == program_id {
+        let oracle = OrderOracle::try_deserialize(&mut &oracle_account.try_borrow_data()?[..])?;
+        require!(
+            oracle.order_status == OrderStatus::Placed,
+            ClickCrateErrors::OracleAlreadyUpdated
+        );
+        return Ok(oracle_pda);
+    }
+
+    let space = 8 + OrderOracle::MAX_SIZE;
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(space);
+    let bump_seed = [bump];
+    let signer_seeds: &[&[u8]] = &[b"oracle", product_account.key.as_ref(), &bump_seed];
+    if oracle_account.lamports() == 0 {
+        create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: oracle_account.clone(),
+                },
+                &[signer_seeds],
+            ),
+            rent_exempt_minimum,
+            space as u64,
+            program_id,
+        )?;
+    } else {
+        // The address was funded ahead of time, so allocate it in place
+        let top_up = rent_exempt_minimum.saturating_sub(oracle_account.lamports());
+        if top_up > 0 {
+            transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    Transfer {
+                        from: payer.clone(),
+                        to: oracle_account.clone(),
+                    },
+                ),
+                top_up,
+            )?;
+        }
+        allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                Allocate {
+                    account_to_allocate: oracle_account.clone(),
+                },
+                &[signer_seeds],
+            ),
+            space as u64,
+        )?;
+        assign(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                Assign {
+                    account_to_assign: oracle_account.clone(),
+                },
+                &[signer_seeds],
+            ),
+            program_id,
+        )?;
+    }
+
+    OrderOracle::new(product_listing.order_manager.clone(), bump)
+        .try_serialize(&mut &mut oracle_account.try_borrow_mut_data()?[..])?;
+    Ok(oracle_pda)
+}
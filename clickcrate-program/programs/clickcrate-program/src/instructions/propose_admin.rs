@@ -0,0 +1,33 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::AdminProposed;
+use crate::state::ProgramConfig;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = admin @ ClickCrateErrors::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    pub admin: Signer<'info>,
+}
+
+/// First step of an admin handover. The current admin stays in control until
+/// `new_admin` accepts, and proposing again replaces the pending admin.
+pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    require!(
+        new_admin != Pubkey::default(),
+        ClickCrateErrors::InvalidPendingAdmin
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.pending_admin = Some(new_admin);
+    emit!(AdminProposed {
+        admin: config.admin,
+        pending_admin: new_admin,
+    });
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::OrderRefunded;
+use crate::payments::VaultPayer;
+use crate::state::{
+    Action, Managed, Order, OrderStatus, ProductListingState, Settlement, VaultAccount,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+#[instruction(product_listing_id: Pubkey)]
+pub struct RefundOrder<'info> {
+    #[account(
+        mut,
+        seeds = [b"listing".as_ref(), product_listing_id.key().as_ref()],
+        bump,
+    )]
+    pub product_listing: Account<'info, ProductListingState>,
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), product_listing_id.as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+    #[account(
+        mut,
+        seeds = [b"order".as_ref(), product_listing_id.as_ref(), &order.id.to_le_bytes()],
+        bump = order.bump,
+    )]
+    pub order: Account<'info, Order>,
+    /// CHECK: This is the buyer's wallet recorded on the order
+    #[account(mut, address = order.buyer)]
+    pub buyer: UncheckedAccount<'info>,
+    #[account(
+        constraint = product_listing.can(&authority.key(), Action::RefundOrder)
+            || authority.key() == order.buyer
+            @ ClickCrateErrors::UnauthorizedUpdate
+    )]
+    pub authority: Signer<'info>,
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub vault_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub buyer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn refund_order(ctx: Context<RefundOrder>, product_listing_id: Pubkey) -> Result<()> {
+    require!(
+        ctx.accounts.order.status == OrderStatus::Cancelled,
+        ClickCrateErrors::OrderNotCancelled
+    );
+    require!(
+        ctx.accounts.order.settlement == Settlement::Unsettled,
+        ClickCrateErrors::OrderAlreadySettled
+    );
+
+    let amount = ctx.accounts.order.total_paid;
+    let vault_bump = [ctx.bumps.vault];
+    let vault_seeds: &[&[u8]] = &[b"vault".as_ref(), product_listing_id.as_ref(), &vault_bump];
+    let vault_payer = VaultPayer::new(
+        &ctx.accounts.product_listing,
+        ctx.accounts.vault.to_account_info(),
+        vault_seeds,
+        ctx.accounts.payment_mint.as_ref(),
+        ctx.accounts.vault_token_account.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+    )?;
+    require!(
+        vault_payer.balance()? >= amount,
+        ClickCrateErrors::InsufficientBalance
+    );
+    vault_payer.pay(
+        &ctx.accounts.buyer.to_account_info(),
+        ctx.accounts.buyer_token_account.as_ref(),
+        amount,
+    )?;
+
+    let order = &mut ctx.accounts.order;
+    order.settlement = Settlement::Refunded;
+    order.updated_at = Clock::get()?.unix_timestamp;
+    ctx.accounts.product_listing.settle_order();
+    msg!("Refunded {} to buyer for order {}", amount, order.id);
+    emit!(OrderRefunded {
+        order: order.key(),
+        order_id: order.id,
+        product_listing_id,
+        product_id: order.product_id,
+        buyer: order.buyer,
+        amount,
+        payment_mint: order.payment_mint,
+    });
+
+    Ok(())
+}
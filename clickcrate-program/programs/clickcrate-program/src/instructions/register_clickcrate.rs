@@ -1,15 +1,18 @@
+use crate::constants::MAX_COMMISSION_BASIS_POINTS;
+use crate::errors::ClickCrateErrors;
+use crate::events::ClickCrateRegistered;
 use crate::state::{ClickCrateState, PlacementType, ProductCategory};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
-#[instruction(id: Pubkey, eligible_placement_type: PlacementType, eligible_product_category: ProductCategory, manager: Pubkey)]
+#[instruction(id: Pubkey, eligible_placement_type: PlacementType, eligible_product_categories: Vec<ProductCategory>, manager: Pubkey, commission_basis_points: u16)]
 pub struct RegisterClickCrate<'info> {
     #[account(
         init,
         seeds = [b"clickcrate".as_ref(), id.key().as_ref()],
         bump,
         payer = owner,
-        space = 8 + ClickCrateState::MAX_SIZE,
+        space = 8 + ClickCrateState::size_for(0),
     )]
     pub clickcrate: Account<'info, ClickCrateState>,
     #[account(mut)]
@@ -21,18 +24,35 @@ pub fn register_clickcrate(
     ctx: Context<RegisterClickCrate>,
     id: Pubkey,
     eligible_placement_type: PlacementType,
-    eligible_product_category: ProductCategory,
+    eligible_product_categories: Vec<ProductCategory>,
     manager: Pubkey,
+    commission_basis_points: u16,
 ) -> Result<()> {
+    require!(
+        commission_basis_points <= MAX_COMMISSION_BASIS_POINTS,
+        ClickCrateErrors::InvalidCommission
+    );
+    ClickCrateState::validate_eligible_product_categories(&eligible_product_categories)?;
     msg!("ClickCrate Registration in progress");
     let clickcrate = &mut ctx.accounts.clickcrate;
+    clickcrate.version = ClickCrateState::VERSION;
     clickcrate.id = id;
     clickcrate.owner = ctx.accounts.owner.key();
     clickcrate.manager = manager;
-    clickcrate.eligible_placement_type = eligible_placement_type;
-    clickcrate.eligible_product_category = eligible_product_category;
-    clickcrate.product = None;
+    clickcrate.commission_basis_points = commission_basis_points;
+    clickcrate.eligible_placement_type = eligible_placement_type.clone();
+    clickcrate.eligible_product_categories = eligible_product_categories.clone();
+    clickcrate.products = Vec::new();
     clickcrate.is_active = false;
+    clickcrate.is_paused = false;
     msg!("ClickCrate Registered");
+    emit!(ClickCrateRegistered {
+        id,
+        owner: clickcrate.owner,
+        manager,
+        eligible_placement_type,
+        eligible_product_categories,
+        commission_basis_points,
+    });
     Ok(())
 }
@@ -1,9 +1,21 @@
-use crate::state::{Origin, PlacementType, ProductCategory, ProductListingState};
+use crate::errors::ClickCrateErrors;
+use crate::events::ProductListingRegistered;
+use crate::state::{
+    Origin, PlacementType, ProductCategory, ProductListingState, ProgramConfig, StockingStatus,
+};
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
 
 #[derive(Accounts)]
 #[instruction(id: Pubkey, origin: Origin, placement_type: PlacementType, product_category: ProductCategory, manager: Pubkey, order_manager: Origin)]
 pub struct RegisterProductListing<'info> {
+    #[account(
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        constraint = config.allows_origin(&origin) && config.allows_origin(&order_manager)
+            @ ClickCrateErrors::OriginNotAllowed,
+    )]
+    pub config: Account<'info, ProgramConfig>,
     #[account(
         init,
         seeds = [b"listing".as_ref(), id.key().as_ref()],
@@ -12,6 +24,7 @@ pub struct RegisterProductListing<'info> {
         space = 8 + ProductListingState::MAX_SIZE,
     )]
     pub product_listing: Account<'info, ProductListingState>,
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
     #[account(mut)]
     pub owner: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -27,6 +40,7 @@ pub fn register_product_listing(
     order_manager: Origin,
 ) -> Result<()> {
     let product_listing = &mut ctx.accounts.product_listing;
+    product_listing.version = ProductListingState::VERSION;
     product_listing.id = id;
     product_listing.origin = origin.clone();
     product_listing.owner = ctx.accounts.owner.key();
@@ -36,6 +50,25 @@ pub fn register_product_listing(
     product_listing.in_stock = 0;
     product_listing.sold = 0;
     product_listing.is_active = false;
+    product_listing.is_paused = false;
     product_listing.order_manager = order_manager.clone();
+    product_listing.payment_mint = ctx.accounts.payment_mint.as_ref().map(|mint| mint.key());
+    product_listing.fees_paid = 0;
+    product_listing.seller_proceeds = 0;
+    product_listing.commissions_paid = 0;
+    product_listing.order_count = 0;
+    product_listing.delivered = 0;
+    product_listing.open_orders = 0;
+    product_listing.begin_stocking(StockingStatus::Idle, 0);
+    emit!(ProductListingRegistered {
+        id,
+        owner: product_listing.owner,
+        manager,
+        origin,
+        placement_type: product_listing.placement_type.clone(),
+        product_category: product_listing.product_category.clone(),
+        order_manager,
+        payment_mint: product_listing.payment_mint,
+    });
     Ok(())
 }
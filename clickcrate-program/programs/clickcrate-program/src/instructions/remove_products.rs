@@ -1,51 +1,85 @@
+use crate::constants::MAX_STOCKING_BATCH;
 use crate::errors::ClickCrateErrors;
-use crate::state::{ClickCrateState, OrderOracle, OrderStatus, ProductListingState, VaultAccount};
+use crate::events::ProductsRemoved;
+use crate::payments::{withdraw_lamports, VaultPayer};
+use crate::state::{
+    Action, ClickCrateState, Managed, OrderOracle, OrderStatus, ProductListingState, ProgramConfig,
+    StockingStatus, VaultAccount,
+};
 use crate::Core;
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use mpl_core::{
     instructions::{
         RemoveExternalPluginAdapterV1CpiBuilder, RemovePluginV1CpiBuilder, UpdatePluginV1CpiBuilder,
     },
     types::{ExternalPluginAdapterKey, FreezeDelegate, Plugin, PluginType},
-    Asset, Collection,
+    Asset,
 };
 
 #[derive(Accounts)]
 #[instruction(product_listing_id: Pubkey, clickcrate_id: Pubkey)]
 pub struct RemoveProducts<'info> {
+    #[account(
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = treasury @ ClickCrateErrors::InvalidTreasury,
+    )]
+    pub config: Account<'info, ProgramConfig>,
     #[account(
         mut,
-        has_one = owner,
         seeds = [b"clickcrate".as_ref(), clickcrate_id.key().as_ref()],
         bump,
     )]
     pub clickcrate: Account<'info, ClickCrateState>,
     #[account(
         mut,
-        has_one = owner,
         seeds = [b"listing".as_ref(), product_listing_id.key().as_ref()],
         bump,
+        constraint = product_listing.can(&authority.key(), Action::RemoveProducts)
+            @ ClickCrateErrors::UnauthorizedUpdate,
     )]
     pub product_listing: Account<'info, ProductListingState>,
     #[account(
         mut,
         seeds = [b"vault".as_ref(), product_listing_id.key().as_ref()],
         bump,
-        close = owner
     )]
     pub vault: Account<'info, VaultAccount>,
-    /// CHECK: This is the Metaplex core collection account
-    #[account(mut)]
+    /// CHECK: This is the Metaplex core collection account, whose address is the listing id
+    #[account(mut, address = product_listing.id)]
     pub listing_collection: UncheckedAccount<'info>,
+    /// Writable so transfer fees withheld in the vault can be harvested before it closes
+    #[account(mut)]
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub vault_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub owner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: This is the protocol treasury wallet, checked against the config
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub treasury: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: The listing owner's wallet, which receives the vault sweep and
+    /// gets back the rent it paid to grow the ClickCrate on placement
+    #[account(mut, address = product_listing.owner)]
+    pub owner: UncheckedAccount<'info>,
+    /// Signs the Core plugin CPIs, so it must also hold authority over the assets
+    #[account(mut)]
+    pub authority: Signer<'info>,
     pub core_program: Program<'info, Core>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
     pub system_program: Program<'info, System>,
 }
 
+/// Unstocks a listing in batches of `[asset, oracle]` pairs passed as remaining
+/// accounts. Every order must be settled first. The vault is swept and the
+/// listing leaves the ClickCrate once the last asset still held by the seller
+/// has been released.
 pub fn remove_products<'a, 'b, 'c: 'info, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, RemoveProducts<'info>>,
-    _product_listing_id: Pubkey,
+    product_listing_id: Pubkey,
     _clickcrate_id: Pubkey,
 ) -> Result<()> {
     let product_listing = &mut ctx.accounts.product_listing;
@@ -55,84 +89,166 @@ pub fn remove_products<'a, 'b, 'c: 'info, 'info>(
     let product_accounts = ctx.remaining_accounts;
 
     require!(
-        product_listing.is_active,
-        ClickCrateErrors::ProductListingDeactivated
-    );
-    require!(
-        clickcrate.is_active,
-        ClickCrateErrors::ClickCrateDeactivated
+        product_listing.clickcrate_pos == Some(clickcrate.id)
+            && clickcrate.products.contains(&product_listing.id),
+        ClickCrateErrors::ProductNotPlaced
     );
     require!(
         product_listing.vault.is_some() && vault.key() == product_listing.vault.unwrap(),
         ClickCrateErrors::InvalidVaultAccount
     );
 
-    let collection_data = listing_collection.try_borrow_data()?;
-    let collection_account = Collection::deserialize(&mut &collection_data[..])?;
-    let total_minted = collection_account.base.num_minted;
+    match product_listing.stocking {
+        StockingStatus::Idle => {
+            require!(
+                product_listing.is_active,
+                ClickCrateErrors::ProductListingDeactivated
+            );
+            require!(
+                clickcrate.is_active,
+                ClickCrateErrors::ClickCrateDeactivated
+            );
+            // The vault is swept at the end, so every order must be paid out or refunded first
+            require!(
+                product_listing.open_orders == 0,
+                ClickCrateErrors::OrdersInProgress
+            );
+            let held = product_listing.in_stock + product_listing.sold - product_listing.delivered;
+            product_listing.begin_stocking(
+                StockingStatus::Removing,
+                u32::try_from(held).map_err(|_| ClickCrateErrors::InvalidRemovalRequest)?,
+            );
+        }
+        StockingStatus::Removing => {}
+        StockingStatus::Placing => return Err(ClickCrateErrors::StockingInProgress.into()),
+    }
 
     require!(
-        product_accounts.len() as u32 == total_minted && (1..=20).contains(&product_accounts.len()),
+        product_accounts.chunks_exact(2).remainder().is_empty()
+            && product_accounts.len() / 2 <= MAX_STOCKING_BATCH,
         ClickCrateErrors::InvalidRemovalRequest
     );
 
-    let core_program_info = &ctx.accounts.core_program;
-    let owner_info = &ctx.accounts.owner;
-    let system_program_info = &ctx.accounts.system_program;
-
-    // Check order status for all products
-    for product_account in product_accounts.iter() {
-        let product_data = product_account.try_borrow_data()?;
-        let deserialized_product = Asset::deserialize(&mut &product_data[..])
-            .map_err(|_| ClickCrateErrors::InvalidProductAccount)?;
-
-        let oracle = deserialized_product
-            .external_plugin_adapter_list
-            .oracles
-            .first()
-            .ok_or(ClickCrateErrors::OracleNotFound)?;
-
-        let oracle_account_info = ctx
-            .remaining_accounts
-            .iter()
-            .find(|a| *a.key == oracle.base_address)
-            .ok_or(ClickCrateErrors::OracleNotFound)?;
-
-        let oracle_data = oracle_account_info.try_borrow_data()?;
-        let oracle_state = OrderOracle::try_deserialize(&mut &oracle_data[..])?;
+    // Check order status for every product in the batch before touching any of them
+    let mut batch = Vec::with_capacity(product_accounts.len() / 2);
+    for accounts in product_accounts.chunks_exact(2) {
+        let (product_account, oracle_account) = (&accounts[0], &accounts[1]);
+        let (oracle_pda, _) = Pubkey::find_program_address(
+            &[b"oracle", product_account.key().as_ref()],
+            ctx.program_id,
+        );
+        require!(
+            oracle_account.key() == oracle_pda,
+            ClickCrateErrors::OracleNotFound
+        );
+
+        // Products already delivered to buyers are no longer the listing's to release
+        let product_owner = Asset::deserialize(&product_account.try_borrow_data()?[..])
+            .map_err(|_| ClickCrateErrors::InvalidProductAccount)?
+            .base
+            .owner;
+        if product_owner != product_listing.owner {
+            continue;
+        }
 
+        let oracle_data = oracle_account.try_borrow_data()?;
+        let oracle_state = OrderOracle::try_deserialize(&mut &oracle_data[..])?;
+        // Completed orders hand the asset to the buyer, so a completed product still held
+        // here was sold before orders were recorded and is released like a refund
         match oracle_state.order_status {
-            OrderStatus::Pending | OrderStatus::Completed | OrderStatus::Cancelled => {}
+            OrderStatus::Placed | OrderStatus::Cancelled | OrderStatus::Completed => {}
+            OrderStatus::Disputed => return Err(ClickCrateErrors::OrderDisputed.into()),
             _ => return Err(ClickCrateErrors::OrdersInProgress.into()),
         }
+        batch.push((
+            product_account,
+            oracle_state.order_status == OrderStatus::Placed,
+        ));
     }
 
-    // Remove plugins and update product listing
-    for product_account in product_accounts.iter() {
+    // Core rejects removing plugins that are already gone, so an asset can't be counted twice
+    let mut removed_products = Vec::with_capacity(batch.len());
+    for (product_account, unsold) in batch {
         remove_product_plugins(
             product_listing,
             product_account,
-            core_program_info,
+            &ctx.accounts.core_program,
             listing_collection,
-            owner_info,
-            system_program_info,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
             ctx.bumps.product_listing,
         )?;
-        product_listing.in_stock -= 1;
+        if unsold {
+            product_listing.in_stock = product_listing.in_stock.saturating_sub(1);
+        }
+        removed_products.push(product_account.key());
     }
+    product_listing.advance_stocking(removed_products.len())?;
 
-    // Transfer vault funds to owner
-    let vault_balance = vault.to_account_info().lamports();
-    if vault_balance > Rent::get()?.minimum_balance(VaultAccount::MAX_SIZE) {
-        let amount_to_transfer =
-            vault_balance - Rent::get()?.minimum_balance(VaultAccount::MAX_SIZE);
-        **vault.to_account_info().try_borrow_mut_lamports()? -= amount_to_transfer;
-        **owner_info.try_borrow_mut_lamports()? += amount_to_transfer;
+    let (mut fee, mut seller_amount) = (0, 0);
+    if product_listing.stocking == StockingStatus::Idle {
+        // Sweep vault funds to the owner, less the protocol fee. No purchase can
+        // open an order mid-removal, so nothing in the vault is owed to a buyer.
+        require!(
+            product_listing.open_orders == 0,
+            ClickCrateErrors::OrdersInProgress
+        );
+        let vault_bump = [ctx.bumps.vault];
+        let vault_seeds: &[&[u8]] = &[b"vault".as_ref(), product_listing_id.as_ref(), &vault_bump];
+        let vault_payer = VaultPayer::new(
+            product_listing,
+            vault.to_account_info(),
+            vault_seeds,
+            ctx.accounts.payment_mint.as_ref(),
+            ctx.accounts.vault_token_account.as_ref(),
+            ctx.accounts.token_program.as_ref(),
+        )?;
+        let vault_balance = vault_payer.balance()?;
+        (fee, seller_amount) = product_listing.split_fee(vault_balance)?;
+        vault_payer.pay(
+            &ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.treasury_token_account.as_ref(),
+            fee,
+        )?;
+        vault_payer.pay(
+            &ctx.accounts.owner.to_account_info(),
+            ctx.accounts.owner_token_account.as_ref(),
+            seller_amount,
+        )?;
+        vault_payer.close_token_account(&ctx.accounts.owner.to_account_info())?;
+        product_listing.fees_paid += fee;
+        product_listing.seller_proceeds += seller_amount;
+
+        // Clear the ClickCrate and ProductListing association
+        clickcrate.products.retain(|id| *id != product_listing.id);
+        product_listing.clickcrate_pos = None;
+        product_listing.vault = None;
+        vault.close(ctx.accounts.owner.to_account_info())?;
+
+        let clickcrate_info = clickcrate.to_account_info();
+        let new_size = 8 + ClickCrateState::size_for(clickcrate.products.len());
+        if new_size < clickcrate_info.data_len() {
+            clickcrate_info.realloc(new_size, false)?;
+            let excess = clickcrate_info
+                .lamports()
+                .saturating_sub(Rent::get()?.minimum_balance(new_size));
+            withdraw_lamports(
+                &clickcrate_info,
+                &ctx.accounts.owner.to_account_info(),
+                excess,
+            )?;
+        }
     }
 
-    // Clear the ClickCrate and ProductListing association
-    clickcrate.product = None;
-    product_listing.clickcrate_pos = None;
+    emit!(ProductsRemoved {
+        product_listing_id,
+        clickcrate_id: clickcrate.id,
+        products: removed_products,
+        protocol_fee: fee,
+        seller_proceeds: seller_amount,
+        in_stock: product_listing.in_stock,
+        remaining: product_listing.stocking_remaining(),
+    });
 
     Ok(())
 }
@@ -142,19 +258,19 @@ fn remove_product_plugins<'info>(
     product_account: &AccountInfo<'info>,
     core_program: &Program<'info, Core>,
     listing_collection: &AccountInfo<'info>,
-    owner: &Signer<'info>,
+    authority: &Signer<'info>,
     system_program: &Program<'info, System>,
     bump: u8,
 ) -> Result<()> {
     let product_data = product_account.try_borrow_data()?;
-    let deserialized_product = Asset::deserialize(&mut &product_data[..])
+    let deserialized_product = Asset::deserialize(&product_data[..])
         .map_err(|_| ClickCrateErrors::InvalidProductAccount)?;
 
     // Unfreeze the Asset
     UpdatePluginV1CpiBuilder::new(core_program)
         .asset(product_account)
         .collection(Some(listing_collection))
-        .payer(owner)
+        .payer(authority)
         .authority(Some(&product_listing.to_account_info()))
         .system_program(system_program)
         .plugin(Plugin::FreezeDelegate(FreezeDelegate { frozen: false }))
@@ -165,8 +281,8 @@ fn remove_product_plugins<'info>(
         RemovePluginV1CpiBuilder::new(core_program)
             .asset(product_account)
             .collection(Some(listing_collection))
-            .payer(owner)
-            .authority(Some(owner))
+            .payer(authority)
+            .authority(Some(authority))
             .system_program(system_program)
             .plugin_type(plugin_type)
             .invoke()?;
@@ -182,8 +298,8 @@ fn remove_product_plugins<'info>(
     RemoveExternalPluginAdapterV1CpiBuilder::new(core_program)
         .asset(product_account)
         .collection(Some(listing_collection))
-        .payer(owner)
-        .authority(Some(owner))
+        .payer(authority)
+        .authority(Some(authority))
         .system_program(system_program)
         .key(ExternalPluginAdapterKey::Oracle(oracle.base_address))
         .invoke()?;
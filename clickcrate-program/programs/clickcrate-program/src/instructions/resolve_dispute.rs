@@ -0,0 +1,227 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::DisputeResolved;
+use crate::instructions::complete_order::release_product;
+use crate::instructions::place_products::StockingAccounts;
+use crate::payments::VaultPayer;
+use crate::state::{
+    ClickCrateState, Order, OrderOracle, OrderStatus, ProductListingState, ProgramConfig,
+    Settlement, VaultAccount,
+};
+use crate::Core;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use mpl_core::Asset;
+
+#[derive(Accounts)]
+#[instruction(product_listing_id: Pubkey)]
+pub struct ResolveDispute<'info> {
+    #[account(
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = treasury @ ClickCrateErrors::InvalidTreasury,
+        has_one = arbiter @ ClickCrateErrors::InvalidArbiter,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(
+        mut,
+        seeds = [b"listing".as_ref(), product_listing_id.key().as_ref()],
+        bump,
+    )]
+    pub product_listing: Account<'info, ProductListingState>,
+    #[account(
+        seeds = [b"clickcrate".as_ref(), order.clickcrate_id.as_ref()],
+        bump,
+    )]
+    pub clickcrate: Account<'info, ClickCrateState>,
+    #[account(
+        mut,
+        seeds = [b"vault".as_ref(), product_listing_id.as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, VaultAccount>,
+    #[account(
+        mut,
+        seeds = [b"oracle".as_ref(), product.key().as_ref()],
+        bump = oracle.bump,
+    )]
+    pub oracle: Account<'info, OrderOracle>,
+    #[account(
+        mut,
+        seeds = [b"order".as_ref(), product_listing_id.as_ref(), &order.id.to_le_bytes()],
+        bump = order.bump,
+        constraint = order.product_id == product.key() @ ClickCrateErrors::OrderNotFound,
+    )]
+    pub order: Account<'info, Order>,
+    /// CHECK: This is the seller's wallet
+    #[account(mut, address = product_listing.owner)]
+    pub seller: UncheckedAccount<'info>,
+    /// CHECK: This is the protocol treasury wallet, checked against the config
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+    /// CHECK: This is the ClickCrate owner's wallet
+    #[account(mut, address = clickcrate.owner)]
+    pub clickcrate_owner: UncheckedAccount<'info>,
+    /// CHECK: This is the buyer's wallet recorded on the order
+    #[account(mut, address = order.buyer)]
+    pub buyer: UncheckedAccount<'info>,
+    /// CHECK: This is a Metaplex Core NFT
+    #[account(mut)]
+    pub product: UncheckedAccount<'info>,
+    /// CHECK: This is the Metaplex core collection account
+    #[account(mut)]
+    pub listing_collection: UncheckedAccount<'info>,
+    /// The arbiter named in the config, who also pays for the Core CPIs
+    #[account(mut)]
+    pub arbiter: Signer<'info>,
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub vault_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub seller_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub clickcrate_owner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub buyer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub core_program: Program<'info, Core>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Settles a disputed order by refunding `buyer_refund` to the buyer and paying
+/// the rest out as a regular sale. A full refund cancels the order and leaves
+/// the product with the seller, anything less delivers it to the buyer.
+pub fn resolve_dispute(
+    ctx: Context<ResolveDispute>,
+    product_listing_id: Pubkey,
+    buyer_refund: u64,
+) -> Result<()> {
+    require!(
+        ctx.accounts.order.settlement == Settlement::Unsettled,
+        ClickCrateErrors::OrderAlreadySettled
+    );
+    require!(
+        ctx.accounts.order.status == OrderStatus::Disputed
+            && ctx.accounts.oracle.order_status == OrderStatus::Disputed,
+        ClickCrateErrors::OrderNotDisputed
+    );
+
+    let amount = ctx.accounts.order.total_paid;
+    let sale_amount = amount
+        .checked_sub(buyer_refund)
+        .ok_or(ClickCrateErrors::InvalidDisputeResolution)?;
+    // A full refund is still possible while payouts are paused
+    if sale_amount > 0 {
+        ctx.accounts.config.require_unpaused(
+            &ctx.accounts.product_listing,
+            Some(&ctx.accounts.clickcrate),
+        )?;
+    }
+    let (fee, remainder) = ctx.accounts.order.split_fee(sale_amount)?;
+    let commission = ctx.accounts.order.commission_for(sale_amount)?;
+    let seller_amount = remainder
+        .checked_sub(commission)
+        .ok_or(ClickCrateErrors::InvalidCommission)?;
+
+    let vault_bump = [ctx.bumps.vault];
+    let vault_seeds: &[&[u8]] = &[b"vault".as_ref(), product_listing_id.as_ref(), &vault_bump];
+    let vault_payer = VaultPayer::new(
+        &ctx.accounts.product_listing,
+        ctx.accounts.vault.to_account_info(),
+        vault_seeds,
+        ctx.accounts.payment_mint.as_ref(),
+        ctx.accounts.vault_token_account.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+    )?;
+    require!(
+        vault_payer.balance()? >= amount,
+        ClickCrateErrors::InsufficientBalance
+    );
+
+    vault_payer.pay(
+        &ctx.accounts.buyer.to_account_info(),
+        ctx.accounts.buyer_token_account.as_ref(),
+        buyer_refund,
+    )?;
+    vault_payer.pay(
+        &ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.treasury_token_account.as_ref(),
+        fee,
+    )?;
+    vault_payer.pay(
+        &ctx.accounts.clickcrate_owner.to_account_info(),
+        ctx.accounts.clickcrate_owner_token_account.as_ref(),
+        commission,
+    )?;
+    vault_payer.pay(
+        &ctx.accounts.seller.to_account_info(),
+        ctx.accounts.seller_token_account.as_ref(),
+        seller_amount,
+    )?;
+
+    let (status, settlement) = match buyer_refund {
+        0 => (OrderStatus::Completed, Settlement::PaidOut),
+        refund if refund == amount => (OrderStatus::Cancelled, Settlement::Refunded),
+        _ => (OrderStatus::Completed, Settlement::Split),
+    };
+    ctx.accounts.oracle.set_order_status(status.clone());
+    if status == OrderStatus::Completed {
+        let product = Asset::deserialize(&ctx.accounts.product.data.borrow()[..])?;
+        require!(
+            product.base.owner == ctx.accounts.seller.key(),
+            ClickCrateErrors::UnauthorizedUpdate
+        );
+        release_product(
+            &ctx.accounts.product_listing,
+            &ctx.accounts.product.to_account_info(),
+            &ctx.accounts.oracle.to_account_info(),
+            &ctx.accounts.buyer.to_account_info(),
+            &StockingAccounts {
+                core_program: ctx.accounts.core_program.to_account_info(),
+                listing_collection: ctx.accounts.listing_collection.to_account_info(),
+                authority: ctx.accounts.arbiter.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            ctx.bumps.product_listing,
+        )?;
+    }
+
+    let order = &mut ctx.accounts.order;
+    order.status = status.clone();
+    order.settlement = settlement;
+    order.protocol_fee = fee;
+    order.commission = commission;
+    order.seller_proceeds = seller_amount;
+    order.updated_at = Clock::get()?.unix_timestamp;
+
+    let product_listing = &mut ctx.accounts.product_listing;
+    product_listing.fees_paid += fee;
+    product_listing.seller_proceeds += seller_amount;
+    product_listing.commissions_paid += commission;
+    if status == OrderStatus::Completed {
+        product_listing.record_delivery(order.quantity);
+    }
+    product_listing.settle_order();
+    msg!(
+        "Dispute resolved: {} refunded to buyer, {} to seller, {} in commission, {} in fees",
+        buyer_refund,
+        seller_amount,
+        commission,
+        fee
+    );
+    emit!(DisputeResolved {
+        order: order.key(),
+        order_id: order.id,
+        product_listing_id,
+        product_id: order.product_id,
+        arbiter: ctx.accounts.arbiter.key(),
+        buyer_refund,
+        protocol_fee: fee,
+        commission,
+        seller_proceeds: seller_amount,
+        payment_mint: order.payment_mint,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::ProductsPlaced;
+use crate::instructions::place_products::{place_batch, StockingAccounts};
+use crate::state::{
+    Action, ClickCrateState, Managed, ProductListingState, ProgramConfig, StockingStatus,
+};
+use crate::Core;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(product_listing_id: Pubkey)]
+pub struct ResumePlacement<'info> {
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(
+        mut,
+        seeds = [b"listing".as_ref(), product_listing_id.key().as_ref()],
+        bump,
+        constraint = product_listing.can(&authority.key(), Action::PlaceProducts)
+            @ ClickCrateErrors::UnauthorizedUpdate,
+    )]
+    pub product_listing: Account<'info, ProductListingState>,
+    #[account(
+        seeds = [b"clickcrate".as_ref(), clickcrate.id.as_ref()],
+        bump,
+        constraint = product_listing.clickcrate_pos == Some(clickcrate.id)
+            @ ClickCrateErrors::ProductNotPlaced,
+    )]
+    pub clickcrate: Account<'info, ClickCrateState>,
+    /// CHECK: This is the Metaplex core collection account, whose address is the listing id
+    #[account(mut, address = product_listing.id)]
+    pub listing_collection: UncheckedAccount<'info>,
+    /// Signs the Core plugin CPIs, so it must also hold authority over the assets
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub core_program: Program<'info, Core>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn resume_placement<'a, 'b, 'c: 'info, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ResumePlacement<'info>>,
+    product_listing_id: Pubkey,
+) -> Result<()> {
+    let product_listing = &mut ctx.accounts.product_listing;
+    require!(
+        product_listing.stocking == StockingStatus::Placing,
+        ClickCrateErrors::InvalidStockingRequest
+    );
+    ctx.accounts
+        .config
+        .require_unpaused(product_listing, Some(&ctx.accounts.clickcrate))?;
+
+    place_batch(
+        product_listing,
+        ctx.remaining_accounts,
+        &StockingAccounts {
+            core_program: ctx.accounts.core_program.to_account_info(),
+            listing_collection: ctx.accounts.listing_collection.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+        ctx.program_id,
+    )?;
+
+    emit!(ProductsPlaced {
+        product_listing_id,
+        clickcrate_id: product_listing
+            .clickcrate_pos
+            .ok_or(ClickCrateErrors::ProductNotPlaced)?,
+        vault: product_listing
+            .vault
+            .ok_or(ClickCrateErrors::InvalidVaultAccount)?,
+        products: ctx
+            .remaining_accounts
+            .chunks_exact(2)
+            .map(|accounts| accounts[0].key())
+            .collect(),
+        price: product_listing.price.unwrap_or_default(),
+        payment_mint: product_listing.payment_mint,
+        in_stock: product_listing.in_stock,
+        remaining: product_listing.stocking_remaining(),
+    });
+
+    Ok(())
+}
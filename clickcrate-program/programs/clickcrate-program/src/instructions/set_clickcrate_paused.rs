@@ -0,0 +1,32 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::PauseChanged;
+use crate::state::{ClickCrateState, ProgramConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetClickCratePaused<'info> {
+    #[account(
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = admin @ ClickCrateErrors::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(
+        mut,
+        seeds = [b"clickcrate".as_ref(), clickcrate.id.as_ref()],
+        bump,
+    )]
+    pub clickcrate: Account<'info, ClickCrateState>,
+    pub admin: Signer<'info>,
+}
+
+pub fn set_clickcrate_paused(ctx: Context<SetClickCratePaused>, paused: bool) -> Result<()> {
+    let clickcrate = &mut ctx.accounts.clickcrate;
+    clickcrate.is_paused = paused;
+    emit!(PauseChanged {
+        account: clickcrate.key(),
+        admin: ctx.accounts.admin.key(),
+        paused,
+    });
+    Ok(())
+}
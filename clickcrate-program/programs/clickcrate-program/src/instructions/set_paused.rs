@@ -0,0 +1,28 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::PauseChanged;
+use crate::state::ProgramConfig;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = admin @ ClickCrateErrors::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    pub admin: Signer<'info>,
+}
+
+/// Circuit breaker for the whole program, see `ProgramConfig::require_unpaused`.
+pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.paused = paused;
+    emit!(PauseChanged {
+        account: config.key(),
+        admin: config.admin,
+        paused,
+    });
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::PauseChanged;
+use crate::state::{ProductListingState, ProgramConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetProductListingPaused<'info> {
+    #[account(
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = admin @ ClickCrateErrors::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(
+        mut,
+        seeds = [b"listing".as_ref(), product_listing.id.as_ref()],
+        bump,
+    )]
+    pub product_listing: Account<'info, ProductListingState>,
+    pub admin: Signer<'info>,
+}
+
+pub fn set_product_listing_paused(
+    ctx: Context<SetProductListingPaused>,
+    paused: bool,
+) -> Result<()> {
+    let product_listing = &mut ctx.accounts.product_listing;
+    product_listing.is_paused = paused;
+    emit!(PauseChanged {
+        account: product_listing.key(),
+        admin: ctx.accounts.admin.key(),
+        paused,
+    });
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::ClickCrateOwnershipTransferred;
+use crate::state::{Action, ClickCrateState, Managed};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct TransferClickCrateOwnership<'info> {
+    #[account(
+        mut,
+        seeds = [b"clickcrate".as_ref(), clickcrate.id.as_ref()],
+        bump,
+        constraint = clickcrate.can(&owner.key(), Action::TransferOwnership)
+            @ ClickCrateErrors::UnauthorizedUpdate,
+    )]
+    pub clickcrate: Account<'info, ClickCrateState>,
+    pub owner: Signer<'info>,
+}
+
+pub fn transfer_clickcrate_ownership(
+    ctx: Context<TransferClickCrateOwnership>,
+    new_owner: Pubkey,
+) -> Result<()> {
+    require!(
+        new_owner != Pubkey::default(),
+        ClickCrateErrors::InvalidNewOwner
+    );
+    let clickcrate = &mut ctx.accounts.clickcrate;
+    let previous_owner = clickcrate.owner;
+    clickcrate.owner = new_owner;
+    emit!(ClickCrateOwnershipTransferred {
+        id: clickcrate.id,
+        previous_owner,
+        new_owner,
+    });
+    msg!("ClickCrate ownership transferred to {}", new_owner);
+    Ok(())
+}
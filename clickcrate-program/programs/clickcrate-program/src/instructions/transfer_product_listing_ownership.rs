@@ -0,0 +1,42 @@
+use crate::errors::ClickCrateErrors;
+use crate::events::ProductListingOwnershipTransferred;
+use crate::state::{Action, Managed, ProductListingState};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct TransferProductListingOwnership<'info> {
+    #[account(
+        mut,
+        seeds = [b"listing".as_ref(), product_listing.id.as_ref()],
+        bump,
+        constraint = product_listing.can(&owner.key(), Action::TransferOwnership)
+            @ ClickCrateErrors::UnauthorizedUpdate,
+    )]
+    pub product_listing: Account<'info, ProductListingState>,
+    pub owner: Signer<'info>,
+}
+
+pub fn transfer_product_listing_ownership(
+    ctx: Context<TransferProductListingOwnership>,
+    new_owner: Pubkey,
+) -> Result<()> {
+    require!(
+        new_owner != Pubkey::default(),
+        ClickCrateErrors::InvalidNewOwner
+    );
+    let product_listing = &mut ctx.accounts.product_listing;
+    // Placed products are still held and delegated by the current owner
+    require!(
+        product_listing.clickcrate_pos.is_none(),
+        ClickCrateErrors::ProductAlreadyPlaced
+    );
+    let previous_owner = product_listing.owner;
+    product_listing.owner = new_owner;
+    emit!(ProductListingOwnershipTransferred {
+        id: product_listing.id,
+        previous_owner,
+        new_owner,
+    });
+    msg!("Product Listing ownership transferred to {}", new_owner);
+    Ok(())
+}
@@ -1,34 +1,63 @@
-use crate::state::{ClickCrateState, PlacementType, ProductCategory};
+use crate::constants::MAX_COMMISSION_BASIS_POINTS;
+use crate::errors::ClickCrateErrors;
+use crate::events::ClickCrateUpdated;
+use crate::state::{Action, ClickCrateState, Managed, PlacementType, ProductCategory};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
-#[instruction(id: Pubkey, eligible_placement_type: PlacementType, eligible_product_category: ProductCategory, manager: Pubkey)]
+#[instruction(id: Pubkey, eligible_placement_type: PlacementType, eligible_product_categories: Vec<ProductCategory>, manager: Pubkey, commission_basis_points: u16)]
 pub struct UpdateClickCrate<'info> {
     #[account(
         mut,
         seeds = [b"clickcrate".as_ref(), id.key().as_ref()],
         bump,
-        realloc = 8 + ClickCrateState::MAX_SIZE,
-        realloc::payer = owner,
-        realloc::zero = true,
+        constraint = clickcrate.can(&authority.key(), Action::Update)
+            @ ClickCrateErrors::UnauthorizedUpdate,
     )]
     pub clickcrate: Account<'info, ClickCrateState>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub authority: Signer<'info>,
 }
 
 pub fn update_clickcrate(
     ctx: Context<UpdateClickCrate>,
     id: Pubkey,
     eligible_placement_type: PlacementType,
-    eligible_product_category: ProductCategory,
+    eligible_product_categories: Vec<ProductCategory>,
     manager: Pubkey,
+    commission_basis_points: u16,
 ) -> Result<()> {
+    require!(
+        commission_basis_points <= MAX_COMMISSION_BASIS_POINTS,
+        ClickCrateErrors::InvalidCommission
+    );
+    ClickCrateState::validate_eligible_product_categories(&eligible_product_categories)?;
     let clickcrate = &mut ctx.accounts.clickcrate;
+    // Placed listings were checked against the current eligibility, so it can only widen
+    if !clickcrate.products.is_empty() {
+        require!(
+            eligible_placement_type == clickcrate.eligible_placement_type
+                && clickcrate
+                    .eligible_product_categories
+                    .iter()
+                    .all(|category| eligible_product_categories.contains(category)),
+            ClickCrateErrors::ProductAlreadyPlaced
+        );
+    }
+    if manager != clickcrate.manager {
+        clickcrate.authorize(&ctx.accounts.authority.key(), Action::AssignManager)?;
+    }
     clickcrate.id = id;
-    clickcrate.eligible_placement_type = eligible_placement_type;
-    clickcrate.eligible_product_category = eligible_product_category;
+    clickcrate.eligible_placement_type = eligible_placement_type.clone();
+    clickcrate.eligible_product_categories = eligible_product_categories.clone();
     clickcrate.manager = manager;
+    clickcrate.commission_basis_points = commission_basis_points;
+    emit!(ClickCrateUpdated {
+        id,
+        authority: ctx.accounts.authority.key(),
+        manager,
+        eligible_placement_type,
+        eligible_product_categories,
+        commission_basis_points,
+    });
     Ok(())
 }
@@ -0,0 +1,64 @@
+use crate::constants::MAX_FEE_BASIS_POINTS;
+use crate::errors::ClickCrateErrors;
+use crate::events::ConfigUpdated;
+use crate::state::{Origin, ProgramConfig};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = admin @ ClickCrateErrors::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, ProgramConfig>,
+    pub admin: Signer<'info>,
+}
+
+pub fn update_config(
+    ctx: Context<UpdateConfig>,
+    fee_basis_points: u16,
+    treasury: Pubkey,
+    arbiter: Pubkey,
+    dispute_window_seconds: i64,
+    allowed_origins: Vec<Origin>,
+    max_clickcrate_products: u8,
+) -> Result<()> {
+    require!(
+        fee_basis_points <= MAX_FEE_BASIS_POINTS,
+        ClickCrateErrors::InvalidFeeBasisPoints
+    );
+    require!(
+        treasury != Pubkey::default(),
+        ClickCrateErrors::InvalidTreasury
+    );
+    require!(
+        arbiter != Pubkey::default(),
+        ClickCrateErrors::InvalidArbiter
+    );
+    require!(
+        dispute_window_seconds >= 0,
+        ClickCrateErrors::InvalidDisputeWindow
+    );
+    ProgramConfig::validate_origins(&allowed_origins)?;
+    ProgramConfig::validate_max_clickcrate_products(max_clickcrate_products)?;
+
+    let config = &mut ctx.accounts.config;
+    config.fee_basis_points = fee_basis_points;
+    config.treasury = treasury;
+    config.arbiter = arbiter;
+    config.dispute_window_seconds = dispute_window_seconds;
+    config.allowed_origins = allowed_origins.clone();
+    config.max_clickcrate_products = max_clickcrate_products;
+    emit!(ConfigUpdated {
+        admin: config.admin,
+        treasury,
+        arbiter,
+        fee_basis_points,
+        dispute_window_seconds,
+        allowed_origins,
+        max_clickcrate_products,
+    });
+    Ok(())
+}
@@ -1,38 +1,39 @@
 use crate::errors::ClickCrateErrors;
+use crate::events::OrderStatusChanged;
 use crate::state::{
-    ExternalValidationResult, OracleValidation, OrderOracle, OrderStatus, ProductListingState,
+    Action, Managed, Order, OrderOracle, OrderStatus, ProductListingState, ProgramConfig,
+    Settlement,
 };
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 #[instruction(product_id: Pubkey, product_listing_id: Pubkey, new_order_status: OrderStatus)]
 pub struct UpdateOrderStatus<'info> {
+    #[account(seeds = [b"config".as_ref()], bump = config.bump)]
+    pub config: Account<'info, ProgramConfig>,
     #[account(
       mut,
-      has_one = owner,
       seeds = [b"listing".as_ref(), product_listing_id.key().as_ref()],
       bump,
+      constraint = product_listing.can(&authority.key(), Action::UpdateOrderStatus)
+          @ ClickCrateErrors::UnauthorizedUpdate,
     )]
     pub product_listing: Account<'info, ProductListingState>,
-    // #[account(
-    //   mut,
-    //   seeds = [b"oracle", product.key().as_ref()],
-    //   bump,
-    //   realloc = 8 + OrderOracle::MAX_SIZE,
-    //   realloc::payer = seller,
-    //   realloc::zero = true,
-    // )]
-    // pub oracle: Account<'info, OrderOracle>,
     #[account(
       mut,
       seeds = [b"oracle".as_ref(), product_id.key().as_ref()],
       bump = oracle.bump,
      )]
     pub oracle: Account<'info, OrderOracle>,
+    #[account(
+      mut,
+      seeds = [b"order".as_ref(), product_listing_id.as_ref(), &order.id.to_le_bytes()],
+      bump = order.bump,
+      constraint = order.product_id == product_id @ ClickCrateErrors::OrderNotFound,
+    )]
+    pub order: Account<'info, Order>,
     #[account(mut)]
-    pub owner: Signer<'info>,
-    #[account(mut)]
-    pub seller: Signer<'info>,
+    pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
@@ -43,36 +44,39 @@ pub fn update_order_status(
     new_order_status: OrderStatus,
 ) -> Result<()> {
     let oracle = &mut ctx.accounts.oracle;
-
+    let order = &mut ctx.accounts.order;
+    require!(
+        order.settlement == Settlement::Unsettled,
+        ClickCrateErrors::OrderAlreadySettled
+    );
+    require!(
+        order.status != OrderStatus::Disputed,
+        ClickCrateErrors::OrderDisputed
+    );
+    // Completion comes from the buyer confirming or the dispute window running out
     require!(
-        ctx.accounts.seller.key() == ctx.accounts.product_listing.owner
-            || ctx.accounts.seller.key() == ctx.accounts.product_listing.manager,
-        ClickCrateErrors::UnauthorizedUpdate
+        order.status.can_transition_to(&new_order_status)
+            && oracle.order_status == order.status
+            && new_order_status != OrderStatus::Completed,
+        ClickCrateErrors::InvalidStatusTransition
     );
 
-    oracle.order_status = new_order_status.clone();
-    oracle.validation = match new_order_status {
-        OrderStatus::Pending => OracleValidation::V1 {
-            create: ExternalValidationResult::Pass,
-            transfer: ExternalValidationResult::Rejected,
-            burn: ExternalValidationResult::Pass,
-            update: ExternalValidationResult::Pass,
-        },
-        OrderStatus::Placed
-        | OrderStatus::Confirmed
-        | OrderStatus::Fulfilled
-        | OrderStatus::Delivered => OracleValidation::V1 {
-            create: ExternalValidationResult::Rejected,
-            transfer: ExternalValidationResult::Rejected,
-            burn: ExternalValidationResult::Rejected,
-            update: ExternalValidationResult::Pass,
-        },
-        OrderStatus::Cancelled | OrderStatus::Completed => OracleValidation::V1 {
-            create: ExternalValidationResult::Approved,
-            transfer: ExternalValidationResult::Approved,
-            burn: ExternalValidationResult::Rejected,
-            update: ExternalValidationResult::Pass,
-        },
-    };
+    let now = Clock::get()?.unix_timestamp;
+    if new_order_status == OrderStatus::Delivered {
+        order.release_at = Some(ctx.accounts.config.release_time(now)?);
+    }
+    let previous_status = std::mem::replace(&mut order.status, new_order_status.clone());
+    order.updated_at = now;
+    oracle.set_order_status(new_order_status.clone());
+    emit!(OrderStatusChanged {
+        order: order.key(),
+        order_id: order.id,
+        product_listing_id: order.product_listing_id,
+        product_id: order.product_id,
+        authority: ctx.accounts.authority.key(),
+        previous_status,
+        new_status: new_order_status,
+        release_at: order.release_at,
+    });
     Ok(())
 }
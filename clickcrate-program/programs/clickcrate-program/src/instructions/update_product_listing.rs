@@ -1,4 +1,6 @@
-use crate::state::{PlacementType, ProductCategory, ProductListingState};
+use crate::errors::ClickCrateErrors;
+use crate::events::ProductListingUpdated;
+use crate::state::{Action, Managed, PlacementType, ProductCategory, ProductListingState};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -8,14 +10,11 @@ pub struct UpdateProductListing<'info> {
         mut,
         seeds = [b"listing".as_ref(), id.key().as_ref()],
         bump,
-        realloc = 8 + ProductListingState::MAX_SIZE,
-        realloc::payer = owner,
-        realloc::zero = true,
+        constraint = product_listing.can(&authority.key(), Action::Update)
+            @ ClickCrateErrors::UnauthorizedUpdate,
     )]
     pub product_listing: Account<'info, ProductListingState>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub authority: Signer<'info>,
 }
 
 pub fn update_product_listing(
@@ -27,9 +26,27 @@ pub fn update_product_listing(
     price: u64,
 ) -> Result<()> {
     let product_listing = &mut ctx.accounts.product_listing;
-    product_listing.placement_type = placement_type;
-    product_listing.product_category = product_category;
+    if manager != product_listing.manager {
+        product_listing.authorize(&ctx.accounts.authority.key(), Action::AssignManager)?;
+    }
+    // Placed listings were checked against their ClickCrate's eligibility
+    require!(
+        product_listing.clickcrate_pos.is_none()
+            || (product_listing.placement_type == placement_type
+                && product_listing.product_category == product_category),
+        ClickCrateErrors::ProductAlreadyPlaced
+    );
+    product_listing.placement_type = placement_type.clone();
+    product_listing.product_category = product_category.clone();
     product_listing.price = Some(price);
     product_listing.manager = manager;
+    emit!(ProductListingUpdated {
+        id: product_listing.id,
+        authority: ctx.accounts.authority.key(),
+        manager,
+        placement_type,
+        product_category,
+        price,
+    });
     Ok(())
 }
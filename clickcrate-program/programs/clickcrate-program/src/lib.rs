@@ -17,9 +17,12 @@ static ALLOC: smalloc::Smalloc<
 
 use anchor_lang::prelude::*;
 
-// pub mod constants;
+pub mod constants;
 pub mod errors;
+pub mod events;
 pub mod instructions;
+pub mod migrations;
+pub mod payments;
 pub mod state;
 
 // use crate::constants::*;
@@ -33,19 +36,68 @@ declare_id!("8haS17iUkRL1aMchExXTcLS5J8iHwupN9gWWRNLD9q3v");
 pub mod clickcrate_program {
     use super::*;
 
+    pub fn initialize_config(ctx: Context<InitializeConfig>, treasury: Pubkey) -> Result<()> {
+        instructions::initialize_config::initialize_config(ctx, treasury)
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        fee_basis_points: u16,
+        treasury: Pubkey,
+        arbiter: Pubkey,
+        dispute_window_seconds: i64,
+        allowed_origins: Vec<Origin>,
+        max_clickcrate_products: u8,
+    ) -> Result<()> {
+        instructions::update_config::update_config(
+            ctx,
+            fee_basis_points,
+            treasury,
+            arbiter,
+            dispute_window_seconds,
+            allowed_origins,
+            max_clickcrate_products,
+        )
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin::propose_admin(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin::accept_admin(ctx)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        instructions::set_paused::set_paused(ctx, paused)
+    }
+
+    pub fn set_clickcrate_paused(ctx: Context<SetClickCratePaused>, paused: bool) -> Result<()> {
+        instructions::set_clickcrate_paused::set_clickcrate_paused(ctx, paused)
+    }
+
+    pub fn set_product_listing_paused(
+        ctx: Context<SetProductListingPaused>,
+        paused: bool,
+    ) -> Result<()> {
+        instructions::set_product_listing_paused::set_product_listing_paused(ctx, paused)
+    }
+
     pub fn register_clickcrate(
         ctx: Context<RegisterClickCrate>,
         id: Pubkey,
         eligible_placement_type: PlacementType,
-        eligible_product_category: ProductCategory,
+        eligible_product_categories: Vec<ProductCategory>,
         manager: Pubkey,
+        commission_basis_points: u16,
     ) -> Result<()> {
         instructions::register_clickcrate::register_clickcrate(
             ctx,
             id,
             eligible_placement_type,
-            eligible_product_category,
+            eligible_product_categories,
             manager,
+            commission_basis_points,
         )
     }
 
@@ -53,15 +105,17 @@ pub mod clickcrate_program {
         ctx: Context<UpdateClickCrate>,
         id: Pubkey,
         eligible_placement_type: PlacementType,
-        eligible_product_category: ProductCategory,
+        eligible_product_categories: Vec<ProductCategory>,
         manager: Pubkey,
+        commission_basis_points: u16,
     ) -> Result<()> {
         instructions::update_clickcrate::update_clickcrate(
             ctx,
             id,
             eligible_placement_type,
-            eligible_product_category,
+            eligible_product_categories,
             manager,
+            commission_basis_points,
         )
     }
 
@@ -103,6 +157,22 @@ pub mod clickcrate_program {
         )
     }
 
+    pub fn transfer_clickcrate_ownership(
+        ctx: Context<TransferClickCrateOwnership>,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        instructions::transfer_clickcrate_ownership::transfer_clickcrate_ownership(ctx, new_owner)
+    }
+
+    pub fn transfer_product_listing_ownership(
+        ctx: Context<TransferProductListingOwnership>,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        instructions::transfer_product_listing_ownership::transfer_product_listing_ownership(
+            ctx, new_owner,
+        )
+    }
+
     pub fn activate_clickcrate(ctx: Context<ActivateClickCrate>) -> Result<()> {
         instructions::activate_clickcrate::activate_clickcrate(ctx)
     }
@@ -119,6 +189,14 @@ pub mod clickcrate_program {
         instructions::deactivate_product_listing::deactivate_product_listing(ctx)
     }
 
+    pub fn close_clickcrate(ctx: Context<CloseClickCrate>) -> Result<()> {
+        instructions::close_clickcrate::close_clickcrate(ctx)
+    }
+
+    pub fn close_product_listing(ctx: Context<CloseProductListing>) -> Result<()> {
+        instructions::close_product_listing::close_product_listing(ctx)
+    }
+
     pub fn initialize_oracle(
         ctx: Context<InitializeOracle>,
         product_listing_id: Pubkey,
@@ -152,6 +230,24 @@ pub mod clickcrate_program {
         instructions::place_products::place_products(ctx, product_listing_id, clickcrate_id, price)
     }
 
+    pub fn resume_placement<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ResumePlacement<'info>>,
+        product_listing_id: Pubkey,
+    ) -> Result<()> {
+        instructions::resume_placement::resume_placement(ctx, product_listing_id)
+    }
+
+    pub fn abort_stocking(ctx: Context<AbortStocking>, product_listing_id: Pubkey) -> Result<()> {
+        instructions::abort_stocking::abort_stocking(ctx, product_listing_id)
+    }
+
+    pub fn add_stock<'a, 'b, 'c: 'info, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, AddStock<'info>>,
+        product_listing_id: Pubkey,
+    ) -> Result<()> {
+        instructions::add_stock::add_stock(ctx, product_listing_id)
+    }
+
     pub fn make_purchase(
         ctx: Context<MakePurchase>,
         product_listing_id: Pubkey,
@@ -182,7 +278,46 @@ pub mod clickcrate_program {
         )
     }
 
+    pub fn confirm_delivery(
+        ctx: Context<ConfirmDelivery>,
+        product_listing_id: Pubkey,
+    ) -> Result<()> {
+        instructions::confirm_delivery::confirm_delivery(ctx, product_listing_id)
+    }
+
+    pub fn open_dispute(ctx: Context<OpenDispute>, product_listing_id: Pubkey) -> Result<()> {
+        instructions::open_dispute::open_dispute(ctx, product_listing_id)
+    }
+
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        product_listing_id: Pubkey,
+        buyer_refund: u64,
+    ) -> Result<()> {
+        instructions::resolve_dispute::resolve_dispute(ctx, product_listing_id, buyer_refund)
+    }
+
     pub fn complete_order(ctx: Context<CompleteOrder>, product_listing_id: Pubkey) -> Result<()> {
         instructions::complete_order::complete_order(ctx, product_listing_id)
     }
+
+    pub fn refund_order(ctx: Context<RefundOrder>, product_listing_id: Pubkey) -> Result<()> {
+        instructions::refund_order::refund_order(ctx, product_listing_id)
+    }
+
+    pub fn migrate_clickcrate(ctx: Context<MigrateClickCrate>) -> Result<()> {
+        instructions::migrate_clickcrate::migrate_clickcrate(ctx)
+    }
+
+    pub fn migrate_product_listing(ctx: Context<MigrateProductListing>) -> Result<()> {
+        instructions::migrate_product_listing::migrate_product_listing(ctx)
+    }
+
+    pub fn migrate_oracle(ctx: Context<MigrateOracle>) -> Result<()> {
+        instructions::migrate_oracle::migrate_oracle(ctx)
+    }
+
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        instructions::migrate_vault::migrate_vault(ctx)
+    }
 }
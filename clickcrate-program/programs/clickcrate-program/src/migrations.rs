@@ -0,0 +1,52 @@
+use crate::errors::ClickCrateErrors;
+use crate::state::Migrate;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+/// Rewrites `account` from an older layout into the current layout of `T`.
+/// Data is carried over unchanged, so anyone can run a migration. The payer
+/// only covers the rent for the extra space.
+pub fn migrate_account<'info, T: Migrate>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let migrated = {
+        let data = account.try_borrow_data()?;
+        match T::from_legacy(&data) {
+            Some(migrated) => migrated,
+            None => {
+                let current =
+                    T::try_deserialize(&mut &data[..]).is_ok_and(|current| current.is_current());
+                return Err(if current {
+                    ClickCrateErrors::AccountAlreadyMigrated
+                } else {
+                    ClickCrateErrors::InvalidAccountVersion
+                }
+                .into());
+            }
+        }
+    };
+
+    // Vaults hold escrow above their rent, so top up by the rent difference alone
+    let space = migrated.space();
+    let rent = Rent::get()?;
+    let top_up = rent
+        .minimum_balance(space)
+        .saturating_sub(rent.minimum_balance(account.data_len()));
+    if top_up > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    account.realloc(space, true)?;
+    migrated.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use crate::errors::ClickCrateErrors;
+use crate::state::ProductListingState;
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_2022_extensions::{harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint},
+    token_interface::{
+        self,
+        spl_token_2022::{
+            extension::{
+                transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+                BaseStateWithExtensions, StateWithExtensions,
+            },
+            state::{Account as AccountState, Mint as MintState},
+        },
+        CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+
+/// Amount the sender has to transfer so that `net_amount` arrives after any
+/// Token-2022 transfer fee configured on the mint.
+pub fn gross_amount_for(mint: &InterfaceAccount<Mint>, net_amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(fee_config) => Ok(fee_config
+            .get_epoch_fee(Clock::get()?.epoch)
+            .calculate_pre_fee_amount(net_amount)
+            .ok_or(ClickCrateErrors::AmountOverflow)?),
+        Err(_) => Ok(net_amount),
+    }
+}
+
+pub fn require_payment_mint(
+    expected: &Pubkey,
+    payment_mint: Option<&InterfaceAccount<Mint>>,
+    token_program: Option<&Interface<TokenInterface>>,
+) -> Result<()> {
+    let payment_mint = payment_mint.ok_or(ClickCrateErrors::MissingPaymentAccounts)?;
+    let token_program = token_program.ok_or(ClickCrateErrors::MissingPaymentAccounts)?;
+    require!(
+        payment_mint.key() == *expected
+            && *payment_mint.to_account_info().owner == token_program.key(),
+        ClickCrateErrors::InvalidPaymentMint
+    );
+    Ok(())
+}
+
+pub fn require_token_account(
+    token_account: &InterfaceAccount<TokenAccount>,
+    mint: &Pubkey,
+    authority: &Pubkey,
+) -> Result<()> {
+    require!(
+        token_account.mint == *mint && token_account.owner == *authority,
+        ClickCrateErrors::InvalidTokenAccount
+    );
+    Ok(())
+}
+
+pub fn require_vault_token_account(
+    vault_token_account: &AccountInfo,
+    vault: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<()> {
+    require!(
+        vault_token_account.key()
+            == get_associated_token_address_with_program_id(vault, mint, token_program),
+        ClickCrateErrors::InvalidTokenAccount
+    );
+    Ok(())
+}
+
+pub fn transfer_tokens<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    from: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from,
+                mint: mint.to_account_info(),
+                to,
+                authority,
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+/// Moves lamports out of a program owned account, keeping it rent exempt.
+pub fn withdraw_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(from.data_len());
+    require!(
+        from.lamports().saturating_sub(rent_exempt_minimum) >= amount,
+        ClickCrateErrors::InsufficientBalance
+    );
+    **from.try_borrow_mut_lamports()? -= amount;
+    **to.try_borrow_mut_lamports()? += amount;
+    Ok(())
+}
+
+pub struct VaultTokens<'a, 'info> {
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub vault_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+/// Pays out of a listing vault, in lamports or in the listing's payment mint.
+pub struct VaultPayer<'a, 'info> {
+    pub vault: AccountInfo<'info>,
+    pub signer_seeds: &'a [&'a [u8]],
+    pub tokens: Option<VaultTokens<'a, 'info>>,
+}
+
+impl<'a, 'info> VaultPayer<'a, 'info> {
+    pub fn new(
+        product_listing: &ProductListingState,
+        vault: AccountInfo<'info>,
+        signer_seeds: &'a [&'a [u8]],
+        payment_mint: Option<&'a InterfaceAccount<'info, Mint>>,
+        vault_token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+        token_program: Option<&'a Interface<'info, TokenInterface>>,
+    ) -> Result<Self> {
+        let tokens = match product_listing.payment_mint {
+            Some(expected_mint) => {
+                require_payment_mint(&expected_mint, payment_mint, token_program)?;
+                let (Some(mint), Some(vault_token_account), Some(token_program)) =
+                    (payment_mint, vault_token_account, token_program)
+                else {
+                    return Err(ClickCrateErrors::MissingPaymentAccounts.into());
+                };
+                require_vault_token_account(
+                    &vault_token_account.to_account_info(),
+                    vault.key,
+                    &expected_mint,
+                    &token_program.key(),
+                )?;
+                Some(VaultTokens {
+                    mint,
+                    vault_token_account,
+                    token_program,
+                })
+            }
+            None => None,
+        };
+        Ok(Self {
+            vault,
+            signer_seeds,
+            tokens,
+        })
+    }
+
+    /// Funds available for payouts when the instruction started.
+    pub fn balance(&self) -> Result<u64> {
+        match &self.tokens {
+            Some(tokens) => Ok(tokens.vault_token_account.amount),
+            None => Ok(self
+                .vault
+                .lamports()
+                .saturating_sub(Rent::get()?.minimum_balance(self.vault.data_len()))),
+        }
+    }
+
+    pub fn pay(
+        &self,
+        recipient: &AccountInfo<'info>,
+        recipient_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+        amount: u64,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        match &self.tokens {
+            Some(tokens) => {
+                let recipient_token_account =
+                    recipient_token_account.ok_or(ClickCrateErrors::MissingPaymentAccounts)?;
+                require_token_account(recipient_token_account, &tokens.mint.key(), recipient.key)?;
+                transfer_tokens(
+                    tokens.token_program,
+                    tokens.mint,
+                    tokens.vault_token_account.to_account_info(),
+                    recipient_token_account.to_account_info(),
+                    self.vault.clone(),
+                    amount,
+                    &[self.signer_seeds],
+                )
+            }
+            None => withdraw_lamports(&self.vault, recipient, amount),
+        }
+    }
+
+    /// Closes the drained vault token account, if any, and sends its rent to
+    /// `destination`. Token-2022 won't close accounts holding withheld transfer
+    /// fees, so those are harvested to the mint first, which must be writable.
+    pub fn close_token_account(&self, destination: &AccountInfo<'info>) -> Result<()> {
+        let Some(tokens) = &self.tokens else {
+            return Ok(());
+        };
+        let vault_token_account = tokens.vault_token_account.to_account_info();
+        let withheld_amount = {
+            let data = vault_token_account.try_borrow_data()?;
+            let account_state = StateWithExtensions::<AccountState>::unpack(&data)?;
+            account_state
+                .get_extension::<TransferFeeAmount>()
+                .map_or(0, |fee_amount| u64::from(fee_amount.withheld_amount))
+        };
+        if withheld_amount > 0 {
+            harvest_withheld_tokens_to_mint(
+                CpiContext::new(
+                    tokens.token_program.to_account_info(),
+                    HarvestWithheldTokensToMint {
+                        token_program_id: tokens.token_program.to_account_info(),
+                        mint: tokens.mint.to_account_info(),
+                    },
+                ),
+                vec![vault_token_account.clone()],
+            )?;
+        }
+        token_interface::close_account(CpiContext::new_with_signer(
+            tokens.token_program.to_account_info(),
+            CloseAccount {
+                account: vault_token_account,
+                destination: destination.clone(),
+                authority: self.vault.clone(),
+            },
+            &[self.signer_seeds],
+        ))
+    }
+}
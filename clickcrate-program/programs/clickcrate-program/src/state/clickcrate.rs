@@ -1,17 +1,62 @@
 use super::enums::{PlacementType, ProductCategory};
+use crate::constants::{MAX_CLICKCRATE_PRODUCTS, MAX_ELIGIBLE_PRODUCT_CATEGORIES};
+use crate::errors::ClickCrateErrors;
 use anchor_lang::prelude::*;
 
 #[account]
 pub struct ClickCrateState {
+    pub version: u8,
     pub id: Pubkey,
     pub owner: Pubkey,
     pub manager: Pubkey,
     pub eligible_placement_type: PlacementType,
-    pub eligible_product_category: ProductCategory,
-    pub product: Option<Pubkey>,
+    pub eligible_product_categories: Vec<ProductCategory>,
+    pub products: Vec<Pubkey>,
     pub is_active: bool,
+    pub commission_basis_points: u16,
+    pub is_paused: bool,
+    pub reserved: [u8; 64],
 }
 
 impl ClickCrateState {
-    pub const MAX_SIZE: usize = 8 + 32 + 32 + 32 + 1 + 1 + (1 + 32) + 1;
+    pub const VERSION: u8 = 1;
+    pub const BASE_SIZE: usize =
+        8 + 1 + 32 + 32 + 32 + 1 + (4 + MAX_ELIGIBLE_PRODUCT_CATEGORIES) + 4 + 1 + 2 + 1 + 64;
+    pub const MAX_SIZE: usize = Self::size_for(MAX_CLICKCRATE_PRODUCTS);
+
+    /// Account size needed to hold `products` placed listings.
+    pub const fn size_for(products: usize) -> usize {
+        Self::BASE_SIZE + 32 * products
+    }
+
+    pub fn validate_eligible_product_categories(categories: &[ProductCategory]) -> Result<()> {
+        require!(
+            !categories.is_empty() && categories.len() <= MAX_ELIGIBLE_PRODUCT_CATEGORIES,
+            ClickCrateErrors::InvalidClickCrateRegistration
+        );
+        for (index, category) in categories.iter().enumerate() {
+            require!(
+                !categories[..index].contains(category),
+                ClickCrateErrors::InvalidClickCrateRegistration
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that a listing matches the placement type and categories this ClickCrate accepts.
+    pub fn require_eligible(
+        &self,
+        placement_type: &PlacementType,
+        product_category: &ProductCategory,
+    ) -> Result<()> {
+        require!(
+            self.eligible_placement_type == *placement_type,
+            ClickCrateErrors::IneligiblePlacementType
+        );
+        require!(
+            self.eligible_product_categories.contains(product_category),
+            ClickCrateErrors::IneligibleProductCategory
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,74 @@
+use super::{enums::Origin, ClickCrateState, ProductListingState};
+use crate::constants::{MAX_ALLOWED_ORIGINS, MAX_CLICKCRATE_PRODUCTS};
+use crate::errors::ClickCrateErrors;
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct ProgramConfig {
+    pub version: u8,
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+    pub treasury: Pubkey,
+    pub arbiter: Pubkey,
+    pub fee_basis_points: u16,
+    pub dispute_window_seconds: i64,
+    pub allowed_origins: Vec<Origin>,
+    pub max_clickcrate_products: u8,
+    pub paused: bool,
+    pub bump: u8,
+    pub reserved: [u8; 64],
+}
+
+impl ProgramConfig {
+    pub const VERSION: u8 = 1;
+    pub const MAX_SIZE: usize =
+        8 + 1 + 32 + (1 + 32) + 32 + 32 + 2 + 8 + (4 + MAX_ALLOWED_ORIGINS) + 1 + 1 + 1 + 64;
+
+    /// Whether listings may name `origin` as their origin or order manager.
+    pub fn allows_origin(&self, origin: &Origin) -> bool {
+        self.allowed_origins.contains(origin)
+    }
+
+    /// Purchases, placements and payouts stop while the program, the listing or
+    /// the ClickCrate it is placed in is paused. Refunds and removals don't check this.
+    pub fn require_unpaused(
+        &self,
+        product_listing: &ProductListingState,
+        clickcrate: Option<&ClickCrateState>,
+    ) -> Result<()> {
+        require!(
+            !self.paused
+                && !product_listing.is_paused
+                && !clickcrate.is_some_and(|clickcrate| clickcrate.is_paused),
+            ClickCrateErrors::ProgramPaused
+        );
+        Ok(())
+    }
+
+    pub fn validate_origins(allowed_origins: &[Origin]) -> Result<()> {
+        require!(
+            allowed_origins.len() <= MAX_ALLOWED_ORIGINS
+                && allowed_origins
+                    .iter()
+                    .enumerate()
+                    .all(|(i, origin)| !allowed_origins[..i].contains(origin)),
+            ClickCrateErrors::InvalidAllowedOrigins
+        );
+        Ok(())
+    }
+
+    pub fn validate_max_clickcrate_products(max_clickcrate_products: u8) -> Result<()> {
+        require!(
+            (1..=MAX_CLICKCRATE_PRODUCTS).contains(&(max_clickcrate_products as usize)),
+            ClickCrateErrors::InvalidConfigLimit
+        );
+        Ok(())
+    }
+
+    /// When funds for an order delivered at `delivered_at` can be released.
+    pub fn release_time(&self, delivered_at: i64) -> Result<i64> {
+        Ok(delivered_at
+            .checked_add(self.dispute_window_seconds)
+            .ok_or(ClickCrateErrors::InvalidDisputeWindow)?)
+    }
+}
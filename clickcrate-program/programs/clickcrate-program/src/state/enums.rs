@@ -56,4 +56,38 @@ pub enum OrderStatus {
     Delivered,
     Completed,
     Cancelled,
+    Disputed,
+}
+
+impl OrderStatus {
+    /// Orders move Pending -> Confirmed -> Fulfilled -> Delivered -> Completed
+    /// and can only be cancelled before they are fulfilled. `Placed` is the
+    /// status of an unsold product and is only left through a purchase, and
+    /// `Disputed` is entered and left only through the dispute instructions.
+    pub fn can_transition_to(&self, next: &OrderStatus) -> bool {
+        matches!(
+            (self, next),
+            (OrderStatus::Pending, OrderStatus::Confirmed)
+                | (OrderStatus::Pending, OrderStatus::Cancelled)
+                | (OrderStatus::Confirmed, OrderStatus::Fulfilled)
+                | (OrderStatus::Confirmed, OrderStatus::Cancelled)
+                | (OrderStatus::Fulfilled, OrderStatus::Delivered)
+                | (OrderStatus::Delivered, OrderStatus::Completed)
+        )
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum StockingStatus {
+    Idle,
+    Placing,
+    Removing,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum Settlement {
+    Unsettled,
+    PaidOut,
+    Refunded,
+    Split,
 }
@@ -0,0 +1,259 @@
+//! Layouts accounts were written with before they carried a version. They are
+//! only read back by the `migrate_*` instructions. The config and orders were
+//! versioned from the start, so they have no legacy layout.
+
+use super::enums::{
+    OracleValidation, OrderStatus, Origin, PlacementType, ProductCategory, StockingStatus,
+};
+use super::{ClickCrateState, OrderOracle, ProductListingState, VaultAccount};
+use anchor_lang::{prelude::*, Discriminator};
+
+/// Accounts that can be rewritten from an older layout without losing data.
+pub trait Migrate: AccountSerialize + AccountDeserialize {
+    /// Whether the account is written in the current layout.
+    fn is_current(&self) -> bool;
+
+    /// Space the account needs in the current layout.
+    fn space(&self) -> usize;
+
+    /// Reads `data` written in an older layout.
+    fn from_legacy(data: &[u8]) -> Option<Self>;
+}
+
+trait LegacyLayout: AnchorDeserialize {
+    /// Space accounts in this layout were allocated with.
+    fn space(&self) -> usize;
+}
+
+/// Reads `data` as `L` under the discriminator of `T`. Versioned layouts are
+/// larger than the space legacy accounts were allocated with, so that space
+/// tells the two apart.
+fn read_legacy<T: Discriminator, L: LegacyLayout>(data: &[u8]) -> Option<L> {
+    if !data.starts_with(&T::DISCRIMINATOR) {
+        return None;
+    }
+    let legacy = L::deserialize(&mut &data[8..]).ok()?;
+    (data.len() == legacy.space()).then_some(legacy)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ClickCrateStateV0 {
+    pub id: Pubkey,
+    pub owner: Pubkey,
+    pub manager: Pubkey,
+    pub eligible_placement_type: PlacementType,
+    pub eligible_product_category: ProductCategory,
+    pub product: Option<Pubkey>,
+    pub is_active: bool,
+}
+
+impl ClickCrateStateV0 {
+    pub const SPACE: usize = 8 + 8 + 32 + 32 + 32 + 1 + 1 + (1 + 32) + 1;
+}
+
+impl LegacyLayout for ClickCrateStateV0 {
+    fn space(&self) -> usize {
+        Self::SPACE
+    }
+}
+
+impl From<ClickCrateStateV0> for ClickCrateState {
+    fn from(legacy: ClickCrateStateV0) -> Self {
+        Self {
+            version: Self::VERSION,
+            id: legacy.id,
+            owner: legacy.owner,
+            manager: legacy.manager,
+            eligible_placement_type: legacy.eligible_placement_type,
+            eligible_product_categories: vec![legacy.eligible_product_category],
+            products: legacy.product.into_iter().collect(),
+            is_active: legacy.is_active,
+            commission_basis_points: 0,
+            is_paused: false,
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl Migrate for ClickCrateState {
+    fn is_current(&self) -> bool {
+        self.version == Self::VERSION
+    }
+
+    fn space(&self) -> usize {
+        8 + Self::size_for(self.products.len())
+    }
+
+    fn from_legacy(data: &[u8]) -> Option<Self> {
+        read_legacy::<Self, ClickCrateStateV0>(data).map(Self::from)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ProductListingStateV0 {
+    pub id: Pubkey,
+    pub origin: Origin,
+    pub owner: Pubkey,
+    pub manager: Pubkey,
+    pub placement_type: PlacementType,
+    pub product_category: ProductCategory,
+    pub in_stock: u64,
+    pub sold: u64,
+    pub clickcrate_pos: Option<Pubkey>,
+    pub is_active: bool,
+    pub price: Option<u64>,
+    pub vault: Option<Pubkey>,
+    pub order_manager: Origin,
+}
+
+impl ProductListingStateV0 {
+    pub const SPACE: usize = 8 + 8 + 32 + 1 + 32 + 32 + 1 + 1 + 8 + 8 + (1 + 32) + 1 + 8 + 32 + 1;
+}
+
+impl LegacyLayout for ProductListingStateV0 {
+    fn space(&self) -> usize {
+        Self::SPACE
+    }
+}
+
+/// Legacy listings predate SPL payments, orders and batched stocking, so those
+/// start out empty. Sales made before orders were recorded stay in `sold` alone.
+impl From<ProductListingStateV0> for ProductListingState {
+    fn from(legacy: ProductListingStateV0) -> Self {
+        Self {
+            version: Self::VERSION,
+            id: legacy.id,
+            origin: legacy.origin,
+            owner: legacy.owner,
+            manager: legacy.manager,
+            placement_type: legacy.placement_type,
+            product_category: legacy.product_category,
+            in_stock: legacy.in_stock,
+            sold: legacy.sold,
+            clickcrate_pos: legacy.clickcrate_pos,
+            is_active: legacy.is_active,
+            price: legacy.price,
+            vault: legacy.vault,
+            order_manager: legacy.order_manager,
+            payment_mint: None,
+            fees_paid: 0,
+            seller_proceeds: 0,
+            commissions_paid: 0,
+            order_count: 0,
+            delivered: 0,
+            stocking: StockingStatus::Idle,
+            stocking_cursor: 0,
+            stocking_target: 0,
+            is_paused: false,
+            open_orders: 0,
+            fee_basis_points: 0,
+            reserved: [0; 64],
+        }
+    }
+}
+
+impl Migrate for ProductListingState {
+    fn is_current(&self) -> bool {
+        self.version == Self::VERSION
+    }
+
+    fn space(&self) -> usize {
+        8 + Self::MAX_SIZE
+    }
+
+    fn from_legacy(data: &[u8]) -> Option<Self> {
+        read_legacy::<Self, ProductListingStateV0>(data).map(Self::from)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct OrderOracleV0 {
+    pub order_status: OrderStatus,
+    pub order_manager: Origin,
+    pub validation: OracleValidation,
+    pub bump: u8,
+}
+
+impl OrderOracleV0 {
+    pub const SPACE: usize = 8 + 8 + 1 + 1 + 5 + 1;
+}
+
+impl LegacyLayout for OrderOracleV0 {
+    fn space(&self) -> usize {
+        Self::SPACE
+    }
+}
+
+/// Legacy sales were paid into the vault at purchase and have no order to
+/// carry them on, so they are all settled as completed. The seller
+/// kept those assets, and removing them releases them like refunds.
+impl From<OrderOracleV0> for OrderOracle {
+    fn from(legacy: OrderOracleV0) -> Self {
+        let mut oracle = Self {
+            validation: legacy.validation,
+            version: Self::VERSION,
+            order_status: legacy.order_status,
+            order_manager: legacy.order_manager,
+            bump: legacy.bump,
+            reserved: [0; 32],
+        };
+        match oracle.order_status {
+            OrderStatus::Placed | OrderStatus::Cancelled => {}
+            _ => oracle.set_order_status(OrderStatus::Completed),
+        }
+        oracle
+    }
+}
+
+impl Migrate for OrderOracle {
+    fn is_current(&self) -> bool {
+        self.version == Self::VERSION
+    }
+
+    fn space(&self) -> usize {
+        8 + Self::MAX_SIZE
+    }
+
+    fn from_legacy(data: &[u8]) -> Option<Self> {
+        read_legacy::<Self, OrderOracleV0>(data).map(Self::from)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct VaultAccountV0 {
+    pub bump: u8,
+}
+
+impl VaultAccountV0 {
+    pub const SPACE: usize = 8 + 8 + 1;
+}
+
+impl LegacyLayout for VaultAccountV0 {
+    fn space(&self) -> usize {
+        Self::SPACE
+    }
+}
+
+impl From<VaultAccountV0> for VaultAccount {
+    fn from(legacy: VaultAccountV0) -> Self {
+        Self {
+            version: Self::VERSION,
+            bump: legacy.bump,
+            reserved: [0; 32],
+        }
+    }
+}
+
+impl Migrate for VaultAccount {
+    fn is_current(&self) -> bool {
+        self.version == Self::VERSION
+    }
+
+    fn space(&self) -> usize {
+        8 + Self::MAX_SIZE
+    }
+
+    fn from_legacy(data: &[u8]) -> Option<Self> {
+        read_legacy::<Self, VaultAccountV0>(data).map(Self::from)
+    }
+}
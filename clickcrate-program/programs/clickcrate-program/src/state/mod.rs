@@ -1,13 +1,21 @@
 pub mod clickcrate;
+pub mod config;
 pub mod enums;
+pub mod legacy;
+pub mod order;
 pub mod order_oracle;
+pub mod permissions;
 pub mod product_listing;
 pub mod utils;
 pub mod vault;
 
 pub use clickcrate::*;
+pub use config::*;
 pub use enums::*;
+pub use legacy::*;
+pub use order::*;
 pub use order_oracle::*;
+pub use permissions::*;
 pub use product_listing::*;
 pub use utils::*;
 pub use vault::*;
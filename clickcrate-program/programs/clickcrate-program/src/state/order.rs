@@ -0,0 +1,82 @@
+use super::enums::{OrderStatus, Settlement};
+use super::utils::basis_points_of;
+use anchor_lang::prelude::*;
+
+#[account]
+pub struct Order {
+    pub version: u8,
+    pub id: u64,
+    pub buyer: Pubkey,
+    pub product_listing_id: Pubkey,
+    pub clickcrate_id: Pubkey,
+    pub product_id: Pubkey,
+    pub quantity: u64,
+    pub unit_price: u64,
+    pub total_paid: u64,
+    pub payment_mint: Option<Pubkey>,
+    pub status: OrderStatus,
+    pub settlement: Settlement,
+    pub fee_basis_points: u16,
+    pub commission_basis_points: u16,
+    pub protocol_fee: u64,
+    pub commission: u64,
+    pub seller_proceeds: u64,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub release_at: Option<i64>,
+    pub bump: u8,
+    pub reserved: [u8; 64],
+}
+
+impl Order {
+    pub const VERSION: u8 = 1;
+    pub const MAX_SIZE: usize = 8
+        + 1
+        + 8
+        + 32
+        + 32
+        + 32
+        + 32
+        + 8
+        + 8
+        + 8
+        + (1 + 32)
+        + 1
+        + 1
+        + 2
+        + 2
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + (1 + 8)
+        + 1
+        + 64;
+
+    /// Splits `amount` into the protocol fee and the remainder, at the fee in
+    /// force when the order was placed.
+    pub fn split_fee(&self, amount: u64) -> Result<(u64, u64)> {
+        let fee = basis_points_of(amount, self.fee_basis_points)?;
+        Ok((fee, amount - fee))
+    }
+
+    /// Share of `amount` paid to the owner of the ClickCrate the order was
+    /// placed through, at the commission in force when it was placed.
+    pub fn commission_for(&self, amount: u64) -> Result<u64> {
+        basis_points_of(amount, self.commission_basis_points)
+    }
+
+    /// Buyers can contest a paid order until it is settled, cancelled or past
+    /// its dispute window.
+    pub fn can_open_dispute(&self, now: i64) -> bool {
+        if self.settlement != Settlement::Unsettled {
+            return false;
+        }
+        match self.status {
+            OrderStatus::Pending | OrderStatus::Confirmed | OrderStatus::Fulfilled => true,
+            OrderStatus::Delivered => self.release_at.is_some_and(|release_at| now < release_at),
+            _ => false,
+        }
+    }
+}
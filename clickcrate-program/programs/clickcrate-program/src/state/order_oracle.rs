@@ -6,25 +6,67 @@ use anchor_lang::prelude::*;
 
 #[account]
 pub struct OrderOracle {
+    // Must stay first: the Oracle adapter reads results at the Anchor offset
+    pub validation: OracleValidation,
+    pub version: u8,
     pub order_status: OrderStatus,
     pub order_manager: Origin,
-    pub validation: OracleValidation,
     pub bump: u8,
+    pub reserved: [u8; 32],
 }
 
 impl OrderOracle {
-    pub const MAX_SIZE: usize = 8 + 1 + 1 + 5 + 1;
+    pub const VERSION: u8 = 1;
+    pub const MAX_SIZE: usize = 8 + 5 + 1 + 1 + 1 + 1 + 32;
 
-    pub fn initialize(&mut self, order_manager: Origin, bump: u8) -> Result<()> {
-        self.order_status = OrderStatus::Placed;
-        self.order_manager = order_manager;
-        self.validation = OracleValidation::V1 {
-            create: ExternalValidationResult::Pass,
-            transfer: ExternalValidationResult::Rejected,
-            burn: ExternalValidationResult::Pass,
-            update: ExternalValidationResult::Pass,
+    pub fn new(order_manager: Origin, bump: u8) -> Self {
+        Self {
+            validation: OracleValidation::V1 {
+                create: ExternalValidationResult::Pass,
+                transfer: ExternalValidationResult::Rejected,
+                burn: ExternalValidationResult::Pass,
+                update: ExternalValidationResult::Pass,
+            },
+            version: Self::VERSION,
+            order_status: OrderStatus::Placed,
+            order_manager,
+            bump,
+            reserved: [0; 32],
+        }
+    }
+
+    /// Moves the oracle to `order_status`, updating what Core lifecycle events
+    /// it lets through.
+    pub fn set_order_status(&mut self, order_status: OrderStatus) {
+        self.validation = match order_status {
+            OrderStatus::Pending => OracleValidation::V1 {
+                create: ExternalValidationResult::Pass,
+                transfer: ExternalValidationResult::Rejected,
+                burn: ExternalValidationResult::Pass,
+                update: ExternalValidationResult::Pass,
+            },
+            OrderStatus::Placed
+            | OrderStatus::Confirmed
+            | OrderStatus::Fulfilled
+            | OrderStatus::Delivered
+            | OrderStatus::Disputed => OracleValidation::V1 {
+                create: ExternalValidationResult::Rejected,
+                transfer: ExternalValidationResult::Rejected,
+                burn: ExternalValidationResult::Rejected,
+                update: ExternalValidationResult::Pass,
+            },
+            OrderStatus::Cancelled | OrderStatus::Completed => OracleValidation::V1 {
+                create: ExternalValidationResult::Approved,
+                transfer: ExternalValidationResult::Approved,
+                burn: ExternalValidationResult::Rejected,
+                update: ExternalValidationResult::Pass,
+            },
         };
-        self.bump = bump;
+        self.order_status = order_status;
+    }
+
+    pub fn initialize(&mut self, order_manager: Origin, bump: u8) -> Result<()> {
+        *self = Self::new(order_manager, bump);
         Ok(())
     }
 }
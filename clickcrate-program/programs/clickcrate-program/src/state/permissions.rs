@@ -0,0 +1,78 @@
+use crate::errors::ClickCrateErrors;
+use crate::state::{ClickCrateState, ProductListingState};
+use anchor_lang::prelude::*;
+
+/// Actions gated on the owner or manager of a ClickCrate or Product Listing.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    SetActive,
+    Update,
+    UpdateOrderStatus,
+    PlaceProducts,
+    RemoveProducts,
+    HostProducts,
+    RefundOrder,
+    AssignManager,
+    Withdraw,
+    TransferOwnership,
+    Close,
+}
+
+impl Action {
+    /// Managers operate the account day to day, but never move funds to
+    /// themselves or change who controls it. A listing's side of placing and
+    /// removing products signs Core CPIs as the holder of the assets, which
+    /// only the owner is, while a ClickCrate's manager can host products.
+    pub fn allows_manager(self) -> bool {
+        match self {
+            Action::SetActive
+            | Action::Update
+            | Action::UpdateOrderStatus
+            | Action::HostProducts
+            | Action::RefundOrder => true,
+            Action::PlaceProducts
+            | Action::RemoveProducts
+            | Action::AssignManager
+            | Action::Withdraw
+            | Action::TransferOwnership
+            | Action::Close => false,
+        }
+    }
+}
+
+pub trait Managed {
+    fn owner(&self) -> Pubkey;
+    fn manager(&self) -> Pubkey;
+
+    fn can(&self, signer: &Pubkey, action: Action) -> bool {
+        *signer == self.owner() || (action.allows_manager() && *signer == self.manager())
+    }
+
+    fn authorize(&self, signer: &Pubkey, action: Action) -> Result<()> {
+        require!(
+            self.can(signer, action),
+            ClickCrateErrors::UnauthorizedUpdate
+        );
+        Ok(())
+    }
+}
+
+impl Managed for ClickCrateState {
+    fn owner(&self) -> Pubkey {
+        self.owner
+    }
+
+    fn manager(&self) -> Pubkey {
+        self.manager
+    }
+}
+
+impl Managed for ProductListingState {
+    fn owner(&self) -> Pubkey {
+        self.owner
+    }
+
+    fn manager(&self) -> Pubkey {
+        self.manager
+    }
+}
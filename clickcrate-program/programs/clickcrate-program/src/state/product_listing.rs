@@ -1,8 +1,11 @@
-use super::enums::{Origin, PlacementType, ProductCategory};
+use super::enums::{Origin, PlacementType, ProductCategory, StockingStatus};
+use super::utils::basis_points_of;
+use crate::errors::ClickCrateErrors;
 use anchor_lang::prelude::*;
 
 #[account]
 pub struct ProductListingState {
+    pub version: u8,
     pub id: Pubkey,
     pub origin: Origin,
     pub owner: Pubkey,
@@ -16,8 +19,96 @@ pub struct ProductListingState {
     pub price: Option<u64>,
     pub vault: Option<Pubkey>,
     pub order_manager: Origin,
+    pub payment_mint: Option<Pubkey>,
+    pub fees_paid: u64,
+    pub seller_proceeds: u64,
+    pub commissions_paid: u64,
+    pub order_count: u64,
+    pub delivered: u64,
+    pub stocking: StockingStatus,
+    pub stocking_cursor: u32,
+    pub stocking_target: u32,
+    pub is_paused: bool,
+    pub open_orders: u64,
+    pub fee_basis_points: u16,
+    pub reserved: [u8; 64],
 }
 
 impl ProductListingState {
-    pub const MAX_SIZE: usize = 8 + 32 + 1 + 32 + 32 + 1 + 1 + 8 + 8 + (1 + 32) + 1 + 8 + 32 + 1;
+    pub const VERSION: u8 = 1;
+    pub const MAX_SIZE: usize = 8
+        + 1
+        + 32
+        + 1
+        + 32
+        + 32
+        + 1
+        + 1
+        + 8
+        + 8
+        + (1 + 32)
+        + 1
+        + (1 + 8)
+        + (1 + 32)
+        + 1
+        + (1 + 32)
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + 4
+        + 4
+        + 1
+        + 8
+        + 2
+        + 64;
+
+    /// Starts a placement or removal that may span several transactions.
+    pub fn begin_stocking(&mut self, stocking: StockingStatus, target: u32) {
+        self.stocking = stocking;
+        self.stocking_cursor = 0;
+        self.stocking_target = target;
+    }
+
+    /// Records a processed batch, going back to idle once every asset is done.
+    pub fn advance_stocking(&mut self, processed: usize) -> Result<()> {
+        let cursor = (processed as u32)
+            .checked_add(self.stocking_cursor)
+            .filter(|cursor| *cursor <= self.stocking_target)
+            .ok_or(ClickCrateErrors::InvalidStockingRequest)?;
+        self.stocking_cursor = cursor;
+        if cursor == self.stocking_target {
+            self.stocking = StockingStatus::Idle;
+        }
+        Ok(())
+    }
+
+    pub fn stocking_remaining(&self) -> u32 {
+        self.stocking_target - self.stocking_cursor
+    }
+
+    /// Splits a vault sweep into the protocol fee and the owner's share, at the
+    /// fee in force when the listing was placed.
+    pub fn split_fee(&self, amount: u64) -> Result<(u64, u64)> {
+        let fee = basis_points_of(amount, self.fee_basis_points)?;
+        Ok((fee, amount - fee))
+    }
+
+    /// Records that an order was paid out, refunded or split.
+    pub fn settle_order(&mut self) {
+        self.open_orders = self.open_orders.saturating_sub(1);
+    }
+
+    /// Units that left the seller's wallet no longer need their plugins removed.
+    pub fn record_delivery(&mut self, quantity: u64) {
+        self.delivered += quantity;
+        if self.stocking == StockingStatus::Removing {
+            self.stocking_target = self
+                .stocking_target
+                .saturating_sub(quantity as u32)
+                .max(self.stocking_cursor);
+        }
+    }
 }
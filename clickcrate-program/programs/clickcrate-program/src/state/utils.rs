@@ -1,3 +1,5 @@
+use crate::constants::MAX_BASIS_POINTS;
+use crate::errors::ClickCrateErrors;
 use anchor_lang::prelude::*;
 use mpl_core::programs::MPL_CORE_ID;
 
@@ -5,6 +7,15 @@ pub trait MaxSize {
     const MAX_SIZE: usize;
 }
 
+/// Share of `amount` expressed in basis points, rounded down.
+pub fn basis_points_of(amount: u64, basis_points: u16) -> Result<u64> {
+    let share = (amount as u128)
+        .checked_mul(basis_points as u128)
+        .ok_or(ClickCrateErrors::AmountOverflow)?
+        / MAX_BASIS_POINTS as u128;
+    Ok(share as u64)
+}
+
 pub struct Core;
 
 impl anchor_lang::Id for Core {
@@ -2,9 +2,12 @@ use anchor_lang::prelude::*;
 
 #[account]
 pub struct VaultAccount {
+    pub version: u8,
     pub bump: u8,
+    pub reserved: [u8; 32],
 }
 
 impl VaultAccount {
-    pub const MAX_SIZE: usize = 8 + 1;
+    pub const VERSION: u8 = 1;
+    pub const MAX_SIZE: usize = 8 + 1 + 1 + 32;
 }
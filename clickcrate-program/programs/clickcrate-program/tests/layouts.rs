@@ -0,0 +1,301 @@
+use anchor_lang::{prelude::*, AccountSerialize, Discriminator};
+use clickcrate_program::{
+    constants::{MAX_ALLOWED_ORIGINS, MAX_CLICKCRATE_PRODUCTS, MAX_ELIGIBLE_PRODUCT_CATEGORIES},
+    state::*,
+};
+
+fn serialize(account: &impl AccountSerialize) -> Vec<u8> {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data).unwrap();
+    data
+}
+
+/// Data of a legacy account as the program allocated it, with its fields
+/// written out byte by byte and padded with zeroes.
+fn legacy_data<T: Discriminator>(fields: &[&[u8]], space: usize) -> Vec<u8> {
+    let mut data = T::DISCRIMINATOR.to_vec();
+    for field in fields {
+        data.extend_from_slice(field);
+    }
+    assert!(data.len() <= space);
+    data.resize(space, 0);
+    data
+}
+
+fn key(seed: u8) -> Pubkey {
+    Pubkey::new_from_array([seed; 32])
+}
+
+fn config() -> ProgramConfig {
+    ProgramConfig {
+        version: ProgramConfig::VERSION,
+        admin: key(1),
+        pending_admin: Some(key(2)),
+        treasury: key(3),
+        arbiter: key(4),
+        fee_basis_points: 100,
+        dispute_window_seconds: 3600,
+        allowed_origins: vec![Origin::Clickcrate; MAX_ALLOWED_ORIGINS],
+        max_clickcrate_products: MAX_CLICKCRATE_PRODUCTS as u8,
+        paused: true,
+        bump: 255,
+        reserved: [0; 64],
+    }
+}
+
+fn clickcrate(products: usize) -> ClickCrateState {
+    ClickCrateState {
+        version: ClickCrateState::VERSION,
+        id: key(1),
+        owner: key(2),
+        manager: key(3),
+        eligible_placement_type: PlacementType::Relatedpurchase,
+        eligible_product_categories: vec![ProductCategory::Books; MAX_ELIGIBLE_PRODUCT_CATEGORIES],
+        products: vec![key(4); products],
+        is_active: true,
+        commission_basis_points: 500,
+        is_paused: false,
+        reserved: [0; 64],
+    }
+}
+
+fn product_listing() -> ProductListingState {
+    ProductListingState {
+        version: ProductListingState::VERSION,
+        id: key(1),
+        origin: Origin::Shopify,
+        owner: key(2),
+        manager: key(3),
+        placement_type: PlacementType::Targetedplacement,
+        product_category: ProductCategory::Health,
+        in_stock: 3,
+        sold: 4,
+        clickcrate_pos: Some(key(4)),
+        is_active: true,
+        price: Some(5),
+        vault: Some(key(5)),
+        order_manager: Origin::Square,
+        payment_mint: Some(key(6)),
+        fees_paid: 6,
+        seller_proceeds: 7,
+        commissions_paid: 8,
+        order_count: 9,
+        delivered: 1,
+        stocking: StockingStatus::Removing,
+        stocking_cursor: 2,
+        stocking_target: 3,
+        is_paused: true,
+        open_orders: 2,
+        fee_basis_points: 0,
+        reserved: [0; 64],
+    }
+}
+
+fn order() -> Order {
+    Order {
+        version: Order::VERSION,
+        id: 1,
+        buyer: key(1),
+        product_listing_id: key(2),
+        clickcrate_id: key(3),
+        product_id: key(4),
+        quantity: 1,
+        unit_price: 2,
+        total_paid: 2,
+        payment_mint: Some(key(5)),
+        status: OrderStatus::Delivered,
+        settlement: Settlement::Unsettled,
+        fee_basis_points: 100,
+        commission_basis_points: 500,
+        protocol_fee: 0,
+        commission: 0,
+        seller_proceeds: 0,
+        created_at: 10,
+        updated_at: 20,
+        release_at: Some(30),
+        bump: 254,
+        reserved: [0; 64],
+    }
+}
+
+#[test]
+fn max_sizes_match_the_largest_accounts() {
+    assert_eq!(serialize(&config()).len(), ProgramConfig::MAX_SIZE);
+    assert_eq!(
+        serialize(&clickcrate(MAX_CLICKCRATE_PRODUCTS)).len(),
+        ClickCrateState::MAX_SIZE
+    );
+    assert_eq!(
+        serialize(&clickcrate(0)).len(),
+        ClickCrateState::size_for(0)
+    );
+    assert_eq!(
+        serialize(&product_listing()).len(),
+        ProductListingState::MAX_SIZE
+    );
+    assert_eq!(serialize(&order()).len(), Order::MAX_SIZE);
+    assert_eq!(
+        serialize(&OrderOracle::new(Origin::Clickcrate, 255)).len(),
+        OrderOracle::MAX_SIZE
+    );
+    assert_eq!(
+        serialize(&VaultAccount {
+            version: VaultAccount::VERSION,
+            bump: 255,
+            reserved: [0; 32],
+        })
+        .len(),
+        VaultAccount::MAX_SIZE
+    );
+}
+
+#[test]
+fn oracle_validation_stays_at_the_anchor_offset() {
+    let mut oracle = OrderOracle::new(Origin::Clickcrate, 255);
+    oracle.set_order_status(OrderStatus::Completed);
+    let data = serialize(&oracle);
+    // V1 tag, then create, transfer, burn and update results
+    assert_eq!(data[8..13], [1, 0, 0, 1, 2]);
+    assert_eq!(data[13], OrderOracle::VERSION);
+}
+
+#[test]
+fn legacy_spaces_match_the_original_allocations() {
+    assert_eq!(ClickCrateStateV0::SPACE, 148);
+    assert_eq!(ProductListingStateV0::SPACE, 206);
+    assert_eq!(OrderOracleV0::SPACE, 24);
+    assert_eq!(VaultAccountV0::SPACE, 17);
+}
+
+#[test]
+fn migrates_legacy_product_listings_without_losing_data() {
+    let data = legacy_data::<ProductListingState>(
+        &[
+            key(1).as_ref(),
+            &[1], // Shopify
+            key(2).as_ref(),
+            key(3).as_ref(),
+            &[2],  // Targetedplacement
+            &[10], // Health
+            &3u64.to_le_bytes(),
+            &4u64.to_le_bytes(),
+            &[1],
+            key(4).as_ref(),
+            &[1],
+            &[1],
+            &5u64.to_le_bytes(),
+            &[1],
+            key(5).as_ref(),
+            &[2], // Square
+        ],
+        ProductListingStateV0::SPACE,
+    );
+
+    let migrated = ProductListingState::from_legacy(&data).unwrap();
+    assert!(migrated.is_current());
+    assert_eq!(migrated.space(), 8 + ProductListingState::MAX_SIZE);
+    let expected = ProductListingState {
+        payment_mint: None,
+        fees_paid: 0,
+        seller_proceeds: 0,
+        commissions_paid: 0,
+        order_count: 0,
+        delivered: 0,
+        stocking: StockingStatus::Idle,
+        stocking_cursor: 0,
+        stocking_target: 0,
+        is_paused: false,
+        open_orders: 0,
+        ..product_listing()
+    };
+    assert_eq!(serialize(&migrated), serialize(&expected));
+}
+
+#[test]
+fn migrates_legacy_clickcrates_with_and_without_a_product() {
+    for product in [None, Some(key(4))] {
+        let product_field = match product {
+            Some(product) => [&[1u8][..], product.as_ref()].concat(),
+            None => vec![0],
+        };
+        let data = legacy_data::<ClickCrateState>(
+            &[
+                key(1).as_ref(),
+                key(2).as_ref(),
+                key(3).as_ref(),
+                &[1], // Relatedpurchase
+                &[2], // Books
+                &product_field,
+                &[1],
+            ],
+            ClickCrateStateV0::SPACE,
+        );
+
+        let migrated = ClickCrateState::from_legacy(&data).unwrap();
+        let expected = ClickCrateState {
+            eligible_product_categories: vec![ProductCategory::Books],
+            products: product.into_iter().collect(),
+            commission_basis_points: 0,
+            ..clickcrate(0)
+        };
+        assert_eq!(
+            migrated.space(),
+            8 + ClickCrateState::size_for(expected.products.len())
+        );
+        assert_eq!(serialize(&migrated), serialize(&expected));
+    }
+}
+
+#[test]
+fn migrates_legacy_oracles_and_vaults() {
+    // Status and manager came first, then the five byte V1 validation
+    let data = legacy_data::<OrderOracle>(
+        &[&[1], &[1], &[1, 2, 1, 2, 2], &[253]],
+        OrderOracleV0::SPACE,
+    );
+    let migrated = OrderOracle::from_legacy(&data).unwrap();
+    assert!(migrated.is_current());
+    assert_eq!(
+        serialize(&migrated),
+        serialize(&OrderOracle::new(Origin::Shopify, 253))
+    );
+
+    // Legacy sales have no order to finish them, so they all settle as completed
+    for status in [0, 2, 3, 4, 5] {
+        let data = legacy_data::<OrderOracle>(
+            &[&[status], &[0], &[1, 2, 1, 2, 2], &[253]],
+            OrderOracleV0::SPACE,
+        );
+        let migrated = OrderOracle::from_legacy(&data).unwrap();
+        assert!(migrated.order_status == OrderStatus::Completed);
+        let mut completed = OrderOracle::new(Origin::Clickcrate, 253);
+        completed.set_order_status(OrderStatus::Completed);
+        assert_eq!(serialize(&migrated), serialize(&completed));
+    }
+
+    let data = legacy_data::<VaultAccount>(&[&[252]], VaultAccountV0::SPACE);
+    let migrated = VaultAccount::from_legacy(&data).unwrap();
+    assert!(migrated.is_current());
+    assert_eq!(migrated.bump, 252);
+}
+
+#[test]
+fn leaves_current_accounts_alone() {
+    let mut data = serialize(&product_listing());
+    data.resize(8 + ProductListingState::MAX_SIZE, 0);
+    assert!(ProductListingState::from_legacy(&data).is_none());
+
+    for products in 0..=MAX_CLICKCRATE_PRODUCTS {
+        let mut data = serialize(&clickcrate(products));
+        data.resize(8 + ClickCrateState::size_for(products), 0);
+        assert!(ClickCrateState::from_legacy(&data).is_none());
+    }
+
+    let mut data = serialize(&OrderOracle::new(Origin::Clickcrate, 255));
+    data.resize(8 + OrderOracle::MAX_SIZE, 0);
+    assert!(OrderOracle::from_legacy(&data).is_none());
+
+    // Accounts of another type never pass for a legacy one
+    let data = legacy_data::<Order>(&[&[1]], VaultAccountV0::SPACE);
+    assert!(VaultAccount::from_legacy(&data).is_none());
+}
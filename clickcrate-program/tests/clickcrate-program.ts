@@ -1,16 +1,898 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+} from "@solana/web3.js";
+import {
+  create,
+  createCollection,
+  fetchAsset,
+  fetchCollection,
+  MPL_CORE_PROGRAM_ID,
+  mplCore,
+} from "@metaplex-foundation/mpl-core";
+import { generateSigner, keypairIdentity } from "@metaplex-foundation/umi";
+import { createUmi } from "@metaplex-foundation/umi-bundle-defaults";
+import {
+  fromWeb3JsKeypair,
+  toWeb3JsPublicKey,
+} from "@metaplex-foundation/umi-web3js-adapters";
+import { expect } from "chai";
 import { ClickcrateProgram } from "../target/types/clickcrate_program";
 
+const BPF_UPGRADEABLE_LOADER_ID = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+const CORE_PROGRAM_ID = toWeb3JsPublicKey(MPL_CORE_PROGRAM_ID);
+
 describe("clickcrate-program", () => {
-  // Configure the client to use the local cluster.
-  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace
+    .ClickcrateProgram as Program<ClickcrateProgram>;
+  const seller = (provider.wallet as anchor.Wallet).payer;
+  const umi = createUmi(provider.connection.rpcEndpoint)
+    .use(mplCore())
+    .use(keypairIdentity(fromWeb3JsKeypair(seller)));
+
+  const price = new anchor.BN(LAMPORTS_PER_SOL);
+  const clickcrateId = Keypair.generate().publicKey;
+  const collectionSigner = generateSigner(umi);
+  const assetSigner = generateSigner(umi);
+  const productListingId = toWeb3JsPublicKey(collectionSigner.publicKey);
+  const productId = toWeb3JsPublicKey(assetSigner.publicKey);
+
+  const pda = (seeds: Buffer[]) =>
+    PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+  const configPda = pda([Buffer.from("config")]);
+  const clickcratePda = pda([
+    Buffer.from("clickcrate"),
+    clickcrateId.toBuffer(),
+  ]);
+  const productListingPda = pda([
+    Buffer.from("listing"),
+    productListingId.toBuffer(),
+  ]);
+  const vaultPda = pda([Buffer.from("vault"), productListingId.toBuffer()]);
+  const oraclePda = pda([Buffer.from("oracle"), productId.toBuffer()]);
+  const orderPda = (orderId: anchor.BN) =>
+    pda([
+      Buffer.from("order"),
+      productListingId.toBuffer(),
+      orderId.toArrayLike(Buffer, "le", 8),
+    ]);
+
+  let orderBuyer: Keypair;
+  let restockId: PublicKey;
+
+  const fundedBuyer = async (lamports: number) => {
+    const buyer = Keypair.generate();
+    const signature = await provider.connection.requestAirdrop(
+      buyer.publicKey,
+      lamports
+    );
+    await provider.connection.confirmTransaction(signature, "confirmed");
+    return buyer;
+  };
+
+  const purchase = async (buyer: Keypair, product = productId) => {
+    const listing = await program.account.productListingState.fetch(
+      productListingPda
+    );
+    return program.methods
+      .makePurchase(
+        productListingId,
+        clickcrateId,
+        product,
+        new anchor.BN(1)
+      )
+      .accountsPartial({
+        config: configPda,
+        clickcrate: clickcratePda,
+        productListing: productListingPda,
+        oracle: pda([Buffer.from("oracle"), product.toBuffer()]),
+        vault: vaultPda,
+        order: orderPda(listing.orderCount),
+        listingCollection: productListingId,
+        productAccount: product,
+        paymentMint: null,
+        buyerTokenAccount: null,
+        vaultTokenAccount: null,
+        owner: seller.publicKey,
+        buyer: buyer.publicKey,
+        coreProgram: CORE_PROGRAM_ID,
+        tokenProgram: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buyer])
+      .rpc();
+  };
+
+  const expectUnauthorized = async (attempt: Promise<unknown>) => {
+    try {
+      await attempt;
+      expect.fail("a foreign signer should be rejected");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      expect((err as anchor.AnchorError).error.errorCode.code).to.equal(
+        "UnauthorizedUpdate"
+      );
+    }
+  };
+
+  before(async () => {
+    const programData = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      BPF_UPGRADEABLE_LOADER_ID
+    )[0];
+    await program.methods
+      .initializeConfig(seller.publicKey)
+      .accountsPartial({
+        config: configPda,
+        program: program.programId,
+        programData,
+        admin: seller.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerClickcrate(
+        clickcrateId,
+        { digitalreplica: {} },
+        [{ clothing: {} }],
+        seller.publicKey,
+        0
+      )
+      .accountsPartial({ clickcrate: clickcratePda, owner: seller.publicKey })
+      .rpc();
+    await program.methods
+      .activateClickcrate()
+      .accountsPartial({
+        clickcrate: clickcratePda,
+        authority: seller.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .registerProductListing(
+        productListingId,
+        { clickcrate: {} },
+        { digitalreplica: {} },
+        { clothing: {} },
+        seller.publicKey,
+        { clickcrate: {} }
+      )
+      .accountsPartial({
+        config: configPda,
+        productListing: productListingPda,
+        paymentMint: null,
+        owner: seller.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .activateProductListing()
+      .accountsPartial({
+        productListing: productListingPda,
+        authority: seller.publicKey,
+      })
+      .rpc();
+
+    await createCollection(umi, {
+      collection: collectionSigner,
+      name: "ClickCrate Test Listing",
+      uri: "https://example.com/listing.json",
+    }).sendAndConfirm(umi);
+    await create(umi, {
+      asset: assetSigner,
+      collection: await fetchCollection(umi, collectionSigner.publicKey),
+      name: "ClickCrate Test Product",
+      uri: "https://example.com/product.json",
+      plugins: [
+        {
+          type: "Attributes",
+          attributeList: [{ key: "Order Status", value: "Placed" }],
+        },
+      ],
+    }).sendAndConfirm(umi);
+
+    await program.methods
+      .initializeOracle(productListingId, productId)
+      .accountsPartial({
+        productListing: productListingPda,
+        product: productId,
+        oracle: oraclePda,
+        payer: seller.publicKey,
+      })
+      .rpc();
+
+    await program.methods
+      .placeProducts(productListingId, clickcrateId, price)
+      .accountsPartial({
+        config: configPda,
+        clickcrate: clickcratePda,
+        productListing: productListingPda,
+        vault: vaultPda,
+        listingCollection: productListingId,
+        paymentMint: null,
+        vaultTokenAccount: null,
+        authority: seller.publicKey,
+        clickcrateAuthority: seller.publicKey,
+        coreProgram: CORE_PROGRAM_ID,
+        tokenProgram: null,
+        associatedTokenProgram: null,
+      })
+      .remainingAccounts([
+        { pubkey: productId, isWritable: true, isSigner: false },
+        { pubkey: oraclePda, isWritable: true, isSigner: false },
+      ])
+      .rpc();
+  });
+
+  it("hands the admin role over in two steps", async () => {
+    const newAdmin = Keypair.generate();
+    const acceptAdmin = (pendingAdmin: Keypair) =>
+      program.methods
+        .acceptAdmin()
+        .accountsPartial({
+          config: configPda,
+          pendingAdmin: pendingAdmin.publicKey,
+        })
+        .signers([pendingAdmin])
+        .rpc();
+    const proposeAdmin = (admin: Keypair, pendingAdmin: PublicKey) =>
+      program.methods
+        .proposeAdmin(pendingAdmin)
+        .accountsPartial({ config: configPda, admin: admin.publicKey })
+        .signers([admin])
+        .rpc();
+
+    await proposeAdmin(seller, newAdmin.publicKey);
+    let config = await program.account.programConfig.fetch(configPda);
+    expect(config.admin.toBase58()).to.equal(seller.publicKey.toBase58());
+    expect(config.pendingAdmin.toBase58()).to.equal(
+      newAdmin.publicKey.toBase58()
+    );
+
+    try {
+      await acceptAdmin(Keypair.generate());
+      expect.fail("only the proposed admin can accept");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      expect((err as anchor.AnchorError).error.errorCode.code).to.equal(
+        "InvalidPendingAdmin"
+      );
+    }
+
+    await acceptAdmin(newAdmin);
+    config = await program.account.programConfig.fetch(configPda);
+    expect(config.admin.toBase58()).to.equal(newAdmin.publicKey.toBase58());
+    expect(config.pendingAdmin).to.be.null;
+
+    await proposeAdmin(newAdmin, seller.publicKey);
+    await acceptAdmin(seller);
+    config = await program.account.programConfig.fetch(configPda);
+    expect(config.admin.toBase58()).to.equal(seller.publicKey.toBase58());
+  });
+
+  it("rejects a purchase from a buyer that cannot cover the price", async () => {
+    const buyer = await fundedBuyer(price.toNumber() / 2);
+    const vaultBalance = await provider.connection.getBalance(vaultPda);
+
+    try {
+      await purchase(buyer);
+      expect.fail("purchase from an unfunded buyer should fail");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      expect((err as anchor.AnchorError).error.errorCode.code).to.equal(
+        "InsufficientBalance"
+      );
+    }
+
+    const listing = await program.account.productListingState.fetch(
+      productListingPda
+    );
+    expect(listing.inStock.toNumber()).to.equal(1);
+    expect(listing.sold.toNumber()).to.equal(0);
+    expect(listing.orderCount.toNumber()).to.equal(0);
+
+    const oracle = await program.account.orderOracle.fetch(oraclePda);
+    expect(oracle.orderStatus).to.deep.equal({ placed: {} });
+    expect(
+      await provider.connection.getAccountInfo(orderPda(new anchor.BN(0)))
+    ).to.be.null;
+    expect(await provider.connection.getBalance(vaultPda)).to.equal(
+      vaultBalance
+    );
+  });
+
+  it("blocks purchases while the program or listing is paused", async () => {
+    const buyer = await fundedBuyer(2 * price.toNumber());
+    const expectPaused = async () => {
+      try {
+        await purchase(buyer);
+        expect.fail("purchases should be blocked while paused");
+      } catch (err) {
+        expect(err).to.be.instanceOf(anchor.AnchorError);
+        expect((err as anchor.AnchorError).error.errorCode.code).to.equal(
+          "ProgramPaused"
+        );
+      }
+    };
+    const setListingPaused = (paused: boolean) =>
+      program.methods
+        .setProductListingPaused(paused)
+        .accountsPartial({
+          config: configPda,
+          productListing: productListingPda,
+          admin: seller.publicKey,
+        })
+        .rpc();
+    const setPaused = (paused: boolean) =>
+      program.methods
+        .setPaused(paused)
+        .accountsPartial({ config: configPda, admin: seller.publicKey })
+        .rpc();
+
+    await setPaused(true);
+    await expectPaused();
+    await setPaused(false);
+
+    await setListingPaused(true);
+    await expectPaused();
+    await setListingPaused(false);
+
+    const listing = await program.account.productListingState.fetch(
+      productListingPda
+    );
+    expect(listing.isPaused).to.be.false;
+    expect(listing.orderCount.toNumber()).to.equal(0);
+  });
+
+  it("reserves inventory once the buyer can pay", async () => {
+    const buyer = await fundedBuyer(2 * price.toNumber());
+    const vaultBalance = await provider.connection.getBalance(vaultPda);
+
+    const signature = await purchase(buyer);
+    orderBuyer = buyer;
+
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const events = [
+      ...new anchor.EventParser(program.programId, program.coder).parseLogs(
+        tx.meta.logMessages
+      ),
+    ];
+    const purchaseMade = events.find((event) => event.name === "purchaseMade");
+    expect(purchaseMade.data.buyer.toBase58()).to.equal(
+      buyer.publicKey.toBase58()
+    );
+    expect(purchaseMade.data.orderId.toNumber()).to.equal(0);
+    expect(purchaseMade.data.totalPaid.toString()).to.equal(price.toString());
+
+    const listing = await program.account.productListingState.fetch(
+      productListingPda
+    );
+    expect(listing.inStock.toNumber()).to.equal(0);
+    expect(listing.sold.toNumber()).to.equal(1);
+
+    const order = await program.account.order.fetch(
+      orderPda(new anchor.BN(0))
+    );
+    expect(order.buyer.toBase58()).to.equal(buyer.publicKey.toBase58());
+    expect(order.totalPaid.toString()).to.equal(price.toString());
+    expect(await provider.connection.getBalance(vaultPda)).to.equal(
+      vaultBalance + price.toNumber()
+    );
+  });
+
+  it("only moves orders along the order lifecycle", async () => {
+    const updateStatus = (status: Record<string, object>) =>
+      program.methods
+        .updateOrderStatus(productId, productListingId, status as any)
+        .accountsPartial({
+          config: configPda,
+          productListing: productListingPda,
+          oracle: oraclePda,
+          order: orderPda(new anchor.BN(0)),
+          authority: seller.publicKey,
+        })
+        .rpc();
+    const expectIllegal = async (status: Record<string, object>) => {
+      try {
+        await updateStatus(status);
+        expect.fail("an illegal transition should be rejected");
+      } catch (err) {
+        expect(err).to.be.instanceOf(anchor.AnchorError);
+        expect((err as anchor.AnchorError).error.errorCode.code).to.equal(
+          "InvalidStatusTransition"
+        );
+      }
+    };
+
+    await expectIllegal({ completed: {} });
+    await updateStatus({ confirmed: {} });
+    await expectIllegal({ pending: {} });
+    await updateStatus({ fulfilled: {} });
+    await expectIllegal({ cancelled: {} });
+
+    const order = await program.account.order.fetch(
+      orderPda(new anchor.BN(0))
+    );
+    expect(order.status).to.deep.equal({ fulfilled: {} });
+  });
+
+  it("keeps funds escrowed until the buyer confirms delivery", async () => {
+    const order = orderPda(new anchor.BN(0));
+    const completeOrder = () =>
+      program.methods
+        .completeOrder(productListingId)
+        .accountsPartial({
+          config: configPda,
+          productListing: productListingPda,
+          clickcrate: clickcratePda,
+          vault: vaultPda,
+          oracle: oraclePda,
+          order,
+          seller: seller.publicKey,
+          treasury: seller.publicKey,
+          clickcrateOwner: seller.publicKey,
+          buyer: orderBuyer.publicKey,
+          product: productId,
+          listingCollection: productListingId,
+          payer: seller.publicKey,
+          paymentMint: null,
+          vaultTokenAccount: null,
+          sellerTokenAccount: null,
+          treasuryTokenAccount: null,
+          clickcrateOwnerTokenAccount: null,
+          coreProgram: CORE_PROGRAM_ID,
+          tokenProgram: null,
+        })
+        .rpc();
+
+    await program.methods
+      .updateOrderStatus(productId, productListingId, { delivered: {} })
+      .accountsPartial({
+        config: configPda,
+        productListing: productListingPda,
+        oracle: oraclePda,
+        order,
+        authority: seller.publicKey,
+      })
+      .rpc();
+    const delivered = await program.account.order.fetch(order);
+    expect(delivered.releaseAt).to.not.be.null;
+
+    try {
+      await completeOrder();
+      expect.fail("funds should stay escrowed during the dispute window");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      expect((err as anchor.AnchorError).error.errorCode.code).to.equal(
+        "DisputeWindowOpen"
+      );
+    }
+
+    await program.methods
+      .confirmDelivery(productListingId)
+      .accountsPartial({
+        oracle: oraclePda,
+        order,
+        buyer: orderBuyer.publicKey,
+      })
+      .signers([orderBuyer])
+      .rpc();
+    await completeOrder();
+
+    const settled = await program.account.order.fetch(order);
+    expect(settled.status).to.deep.equal({ completed: {} });
+    expect(settled.settlement).to.deep.equal({ paidOut: {} });
+    const asset = await fetchAsset(umi, assetSigner.publicKey);
+    expect(asset.owner.toString()).to.equal(orderBuyer.publicKey.toBase58());
+  });
+
+  it("rejects ClickCrate updates from a foreign signer", async () => {
+    const intruder = await fundedBuyer(LAMPORTS_PER_SOL);
+
+    await expectUnauthorized(
+      program.methods
+        .updateClickcrate(
+          clickcrateId,
+          { digitalreplica: {} },
+          [{ clothing: {} }],
+          intruder.publicKey,
+          10_000
+        )
+        .accountsPartial({
+          clickcrate: clickcratePda,
+          authority: intruder.publicKey,
+        })
+        .signers([intruder])
+        .rpc()
+    );
+    await expectUnauthorized(
+      program.methods
+        .transferClickcrateOwnership(intruder.publicKey)
+        .accountsPartial({
+          clickcrate: clickcratePda,
+          owner: intruder.publicKey,
+        })
+        .signers([intruder])
+        .rpc()
+    );
+
+    const clickcrate = await program.account.clickCrateState.fetch(
+      clickcratePda
+    );
+    expect(clickcrate.owner.toBase58()).to.equal(seller.publicKey.toBase58());
+    expect(clickcrate.manager.toBase58()).to.equal(
+      seller.publicKey.toBase58()
+    );
+    expect(clickcrate.commissionBasisPoints).to.equal(0);
+  });
+
+  it("rejects Product Listing updates from a foreign signer", async () => {
+    const intruder = await fundedBuyer(LAMPORTS_PER_SOL);
+
+    await expectUnauthorized(
+      program.methods
+        .updateProductListing(
+          productListingId,
+          { clickcrate: {} },
+          { clothing: {} },
+          intruder.publicKey,
+          new anchor.BN(1)
+        )
+        .accountsPartial({
+          productListing: productListingPda,
+          authority: intruder.publicKey,
+        })
+        .signers([intruder])
+        .rpc()
+    );
+    await expectUnauthorized(
+      program.methods
+        .transferProductListingOwnership(intruder.publicKey)
+        .accountsPartial({
+          productListing: productListingPda,
+          owner: intruder.publicKey,
+        })
+        .signers([intruder])
+        .rpc()
+    );
+
+    const listing = await program.account.productListingState.fetch(
+      productListingPda
+    );
+    expect(listing.owner.toBase58()).to.equal(seller.publicKey.toBase58());
+    expect(listing.manager.toBase58()).to.equal(seller.publicKey.toBase58());
+    expect(listing.price.toString()).to.equal(price.toString());
+  });
+
+  it("keeps a placed listing and its ClickCrate open and eligible", async () => {
+    const expectPlaced = async (attempt: Promise<unknown>) => {
+      try {
+        await attempt;
+        expect.fail("placed accounts should not be closed or narrowed");
+      } catch (err) {
+        expect(err).to.be.instanceOf(anchor.AnchorError);
+        expect((err as anchor.AnchorError).error.errorCode.code).to.equal(
+          "ProductAlreadyPlaced"
+        );
+      }
+    };
+
+    await expectPlaced(
+      program.methods
+        .closeProductListing()
+        .accountsPartial({
+          productListing: productListingPda,
+          vault: vaultPda,
+          owner: seller.publicKey,
+        })
+        .rpc()
+    );
+    await expectPlaced(
+      program.methods
+        .closeClickcrate()
+        .accountsPartial({
+          clickcrate: clickcratePda,
+          owner: seller.publicKey,
+        })
+        .rpc()
+    );
+    await expectPlaced(
+      program.methods
+        .updateClickcrate(
+          clickcrateId,
+          { digitalreplica: {} },
+          [{ books: {} }],
+          seller.publicKey,
+          0
+        )
+        .accountsPartial({
+          clickcrate: clickcratePda,
+          authority: seller.publicKey,
+        })
+        .rpc()
+    );
+
+    const listing = await program.account.productListingState.fetch(
+      productListingPda
+    );
+    expect(listing.clickcratePos.toBase58()).to.equal(clickcrateId.toBase58());
+  });
+
+  it("restocks a listing that has already sold", async () => {
+    const restockSigner = generateSigner(umi);
+    restockId = toWeb3JsPublicKey(restockSigner.publicKey);
+    await create(umi, {
+      asset: restockSigner,
+      collection: await fetchCollection(umi, collectionSigner.publicKey),
+      name: "ClickCrate Test Product",
+      uri: "https://example.com/product.json",
+      plugins: [
+        {
+          type: "Attributes",
+          attributeList: [{ key: "Order Status", value: "Placed" }],
+        },
+      ],
+    }).sendAndConfirm(umi);
+    const restockOracle = pda([Buffer.from("oracle"), restockId.toBuffer()]);
+
+    await program.methods
+      .addStock(productListingId)
+      .accountsPartial({
+        config: configPda,
+        productListing: productListingPda,
+        listingCollection: productListingId,
+        authority: seller.publicKey,
+        coreProgram: CORE_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: restockId, isWritable: true, isSigner: false },
+        { pubkey: restockOracle, isWritable: true, isSigner: false },
+      ])
+      .rpc();
+
+    const listing = await program.account.productListingState.fetch(
+      productListingPda
+    );
+    expect(listing.inStock.toNumber()).to.equal(1);
+    expect(listing.sold.toNumber()).to.equal(1);
+    expect(listing.orderCount.toNumber()).to.equal(1);
+    expect(listing.vault.toBase58()).to.equal(vaultPda.toBase58());
+
+    const oracle = await program.account.orderOracle.fetch(restockOracle);
+    expect(oracle.orderStatus).to.deep.equal({ placed: {} });
+  });
+
+  it("lets the arbiter refund a disputed order", async () => {
+    const buyer = await fundedBuyer(2 * price.toNumber());
+    const listing = await program.account.productListingState.fetch(
+      productListingPda
+    );
+    const order = orderPda(listing.orderCount);
+    const restockOracle = pda([Buffer.from("oracle"), restockId.toBuffer()]);
+    await purchase(buyer, restockId);
+
+    await program.methods
+      .openDispute(productListingId)
+      .accountsPartial({ oracle: restockOracle, order, buyer: buyer.publicKey })
+      .signers([buyer])
+      .rpc();
+
+    try {
+      await program.methods
+        .updateOrderStatus(restockId, productListingId, { confirmed: {} })
+        .accountsPartial({
+          config: configPda,
+          productListing: productListingPda,
+          oracle: restockOracle,
+          order,
+          authority: seller.publicKey,
+        })
+        .rpc();
+      expect.fail("a disputed order should only move through arbitration");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      expect((err as anchor.AnchorError).error.errorCode.code).to.equal(
+        "OrderDisputed"
+      );
+    }
+
+    const buyerBalance = await provider.connection.getBalance(buyer.publicKey);
+    await program.methods
+      .resolveDispute(productListingId, price)
+      .accountsPartial({
+        config: configPda,
+        productListing: productListingPda,
+        clickcrate: clickcratePda,
+        vault: vaultPda,
+        oracle: restockOracle,
+        order,
+        seller: seller.publicKey,
+        treasury: seller.publicKey,
+        clickcrateOwner: seller.publicKey,
+        buyer: buyer.publicKey,
+        product: restockId,
+        listingCollection: productListingId,
+        arbiter: seller.publicKey,
+        paymentMint: null,
+        vaultTokenAccount: null,
+        sellerTokenAccount: null,
+        treasuryTokenAccount: null,
+        clickcrateOwnerTokenAccount: null,
+        buyerTokenAccount: null,
+        coreProgram: CORE_PROGRAM_ID,
+        tokenProgram: null,
+      })
+      .rpc();
+
+    const resolved = await program.account.order.fetch(order);
+    expect(resolved.status).to.deep.equal({ cancelled: {} });
+    expect(resolved.settlement).to.deep.equal({ refunded: {} });
+    expect(await provider.connection.getBalance(buyer.publicKey)).to.equal(
+      buyerBalance + price.toNumber()
+    );
+  });
+
+  it("places a large collection across several transactions", async () => {
+    const batchCollection = generateSigner(umi);
+    const batchListingId = toWeb3JsPublicKey(batchCollection.publicKey);
+    const batchListingPda = pda([
+      Buffer.from("listing"),
+      batchListingId.toBuffer(),
+    ]);
+    await program.methods
+      .registerProductListing(
+        batchListingId,
+        { clickcrate: {} },
+        { digitalreplica: {} },
+        { clothing: {} },
+        seller.publicKey,
+        { clickcrate: {} }
+      )
+      .accountsPartial({
+        config: configPda,
+        productListing: batchListingPda,
+        paymentMint: null,
+        owner: seller.publicKey,
+      })
+      .rpc();
+    await program.methods
+      .activateProductListing()
+      .accountsPartial({
+        productListing: batchListingPda,
+        authority: seller.publicKey,
+      })
+      .rpc();
+    await createCollection(umi, {
+      collection: batchCollection,
+      name: "ClickCrate Batch Listing",
+      uri: "https://example.com/listing.json",
+    }).sendAndConfirm(umi);
+
+    const assets: PublicKey[] = [];
+    for (let i = 0; i < 3; i++) {
+      const signer = generateSigner(umi);
+      await create(umi, {
+        asset: signer,
+        collection: await fetchCollection(umi, batchCollection.publicKey),
+        name: `ClickCrate Batch Product ${i}`,
+        uri: "https://example.com/product.json",
+      }).sendAndConfirm(umi);
+      assets.push(toWeb3JsPublicKey(signer.publicKey));
+    }
+    const asRemaining = (keys: PublicKey[]) =>
+      keys.flatMap((asset) =>
+        [asset, pda([Buffer.from("oracle"), asset.toBuffer()])].map(
+          (pubkey) => ({ pubkey, isWritable: true, isSigner: false })
+        )
+      );
+
+    await program.methods
+      .placeProducts(batchListingId, clickcrateId, price)
+      .accountsPartial({
+        config: configPda,
+        clickcrate: clickcratePda,
+        productListing: batchListingPda,
+        vault: pda([Buffer.from("vault"), batchListingId.toBuffer()]),
+        listingCollection: batchListingId,
+        paymentMint: null,
+        vaultTokenAccount: null,
+        authority: seller.publicKey,
+        clickcrateAuthority: seller.publicKey,
+        coreProgram: CORE_PROGRAM_ID,
+        tokenProgram: null,
+        associatedTokenProgram: null,
+      })
+      .remainingAccounts(asRemaining(assets.slice(0, 2)))
+      .rpc();
+
+    let listing = await program.account.productListingState.fetch(
+      batchListingPda
+    );
+    expect(listing.stocking).to.deep.equal({ placing: {} });
+    expect(listing.stockingCursor).to.equal(2);
+    expect(listing.stockingTarget).to.equal(3);
+
+    await program.methods
+      .resumePlacement(batchListingId)
+      .accountsPartial({
+        config: configPda,
+        productListing: batchListingPda,
+        listingCollection: batchListingId,
+        authority: seller.publicKey,
+        coreProgram: CORE_PROGRAM_ID,
+      })
+      .remainingAccounts(asRemaining(assets.slice(2)))
+      .rpc();
+
+    listing = await program.account.productListingState.fetch(
+      batchListingPda
+    );
+    expect(listing.stocking).to.deep.equal({ idle: {} });
+    expect(listing.inStock.toNumber()).to.equal(3);
+  });
+
+  it("lets a manager operate a listing without owner rights", async () => {
+    const manager = await fundedBuyer(LAMPORTS_PER_SOL);
+    const updateListing = (authority: Keypair, newManager: PublicKey) =>
+      program.methods
+        .updateProductListing(
+          productListingId,
+          { clickcrate: {} },
+          { clothing: {} },
+          newManager,
+          price.muln(2)
+        )
+        .accountsPartial({
+          productListing: productListingPda,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    await updateListing(seller, manager.publicKey);
+    await updateListing(manager, manager.publicKey);
+    await program.methods
+      .deactivateProductListing()
+      .accountsPartial({
+        productListing: productListingPda,
+        authority: manager.publicKey,
+      })
+      .signers([manager])
+      .rpc();
+
+    let listing = await program.account.productListingState.fetch(
+      productListingPda
+    );
+    expect(listing.isActive).to.be.false;
+    expect(listing.price.toString()).to.equal(price.muln(2).toString());
 
-  const program = anchor.workspace.ClickcrateProgram as Program<ClickcrateProgram>;
+    await expectUnauthorized(updateListing(manager, seller.publicKey));
+    await expectUnauthorized(
+      program.methods
+        .transferProductListingOwnership(manager.publicKey)
+        .accountsPartial({
+          productListing: productListingPda,
+          owner: manager.publicKey,
+        })
+        .signers([manager])
+        .rpc()
+    );
 
-  it("Is initialized!", async () => {
-    // Add your test here.
-    const tx = await program.methods.initialize().rpc();
-    console.log("Your transaction signature", tx);
+    listing = await program.account.productListingState.fetch(
+      productListingPda
+    );
+    expect(listing.owner.toBase58()).to.equal(seller.publicKey.toBase58());
+    expect(listing.manager.toBase58()).to.equal(manager.publicKey.toBase58());
   });
 });